@@ -0,0 +1,30 @@
+//! Generate the voicings of every built-in chord in standard tuning, for the library to embed.
+
+use chord_generator::{generate_voicings, Chord, Note, Options, DEFAULT_TUNING};
+use std::{env, fs, path::Path};
+use strum::IntoEnumIterator;
+
+#[path = "src/format.rs"]
+mod format;
+
+fn main() {
+    let options = Options::default();
+    let mut offsets = vec![0; format::ENTRIES + 1];
+    let mut data = Vec::new();
+    for root in Note::iter() {
+        for (i, chord) in Chord::builtins().enumerate() {
+            offsets[format::entry(root, i)] = data.len() as u32;
+            for v in generate_voicings(root, chord, DEFAULT_TUNING, &options) {
+                data.extend(v.frets.iter().map(|f| f.0.unwrap_or(format::MUTED)));
+            }
+        }
+    }
+    offsets[format::ENTRIES] = data.len() as u32;
+
+    let mut db: Vec<u8> = offsets.iter().flat_map(|o| o.to_le_bytes()).collect();
+    db.extend(data);
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("voicings.bin");
+    fs::write(out, db).unwrap();
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/format.rs");
+}
@@ -0,0 +1,20 @@
+//! Layout of the voicing database, shared by the build script writing it and the library
+//! reading it.
+//!
+//! The database starts with a table of little-endian `u32` offsets into the voicing data, one for
+//! each root and built-in quality and a last one for the end of the data. The voicings of a chord
+//! follow each other from its offset up to the next one, a byte per string, from the lowest.
+
+use chord_generator::{Note, QUALITIES};
+
+/// Byte of a muted string, any other is the fret played.
+pub const MUTED: u8 = u8::MAX;
+
+/// Number of chords in the database, every root of every built-in quality.
+pub const ENTRIES: usize = 12 * QUALITIES.len();
+
+/// Position of the chord in the offset table, `chord` being the index of its quality in
+/// [`QUALITIES`].
+pub fn entry(root: Note, chord: usize) -> usize {
+    root as usize * QUALITIES.len() + chord
+}
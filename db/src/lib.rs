@@ -0,0 +1,42 @@
+//! The voicings of every built-in chord in standard tuning with the default [`Options`], generated
+//! when the crate is built so they can be looked up without generating anything.
+//!
+//! ```
+//! use chord_generator::{Chord, Note};
+//!
+//! let voicings = chord_generator_db::lookup(Note::C, Chord::MAJOR).unwrap();
+//! assert!(!voicings.is_empty());
+//! ```
+
+use chord_generator::{Chord, Finger, Note, Options, Voicing, DEFAULT_TUNING};
+
+mod format;
+
+/// The database written by the build script, see [`format`].
+static DATABASE: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/voicings.bin"));
+
+/// Offset of the `i`th chord's voicings in the data following the offset table.
+fn offset(i: usize) -> usize {
+    let bytes = DATABASE[i * 4..i * 4 + 4].try_into().unwrap();
+    u32::from_le_bytes(bytes) as usize
+}
+
+/// The voicings of `chord` built on `root` in standard tuning, best first, as
+/// [`generate_voicings`](chord_generator::generate_voicings) returns them with the default
+/// [`Options`]. `None` for qualities that aren't built in.
+pub fn lookup(root: Note, chord: Chord) -> Option<Vec<Voicing>> {
+    let i = format::entry(root, Chord::builtins().position(|c| c == chord)?);
+    let data = &DATABASE[(format::ENTRIES + 1) * 4..];
+    let options = Options::default();
+    let voicings = data[offset(i)..offset(i + 1)]
+        .chunks(DEFAULT_TUNING.len())
+        .map(|frets| {
+            let frets = frets
+                .iter()
+                .map(|&x| Finger((x != format::MUTED).then_some(x)))
+                .collect();
+            Voicing::new(root, chord, DEFAULT_TUNING, frets, &options)
+        })
+        .collect();
+    Some(voicings)
+}
@@ -0,0 +1,38 @@
+//! Print the voicings of chord symbols in standard tuning from the database, instantly.
+
+use chord_generator::{ChordSymbol, Voicing};
+use chord_generator_db::lookup;
+use serde::Serialize;
+use std::process::exit;
+
+/// Voicings of one of the chord symbols asked for
+#[derive(Serialize)]
+struct Entry {
+    symbol: String,
+    voicings: Vec<Voicing>,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.is_empty() {
+        eprintln!("usage: chord-lookup <SYMBOL>...");
+        exit(2);
+    }
+    let entries: Vec<Entry> = args
+        .into_iter()
+        .map(|symbol| {
+            let parsed: ChordSymbol = symbol.parse().unwrap_or_else(|e| {
+                eprintln!("error: {}", e);
+                exit(2);
+            });
+            if parsed.bass.is_some() {
+                eprintln!("error: slash chords like {} aren't in the database", symbol);
+                exit(2);
+            }
+            // symbols only parse to built-in qualities, which are all there
+            let voicings = lookup(parsed.root, parsed.chord).unwrap();
+            Entry { symbol, voicings }
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&entries).unwrap());
+}
@@ -0,0 +1,195 @@
+//! Chord sheets of music, named from MIDI files or read from charts.
+
+use crate::{identify, Chord, ChordSymbol, NoteSet, ParseError, Pitch};
+use midly::{MetaMessage, MidiMessage, Smf, Timing, TrackEventKind};
+use std::fmt;
+
+/// The channel General MIDI plays drums on, counting from 0.
+const DRUMS: u8 = 9;
+
+/// The chords of a piece, as they change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChordSheet {
+    /// Beats in each bar, from the piece's first time signature.
+    pub beats_per_bar: usize,
+    /// How many bars the piece lasts.
+    pub bars: usize,
+    pub changes: Vec<ChordChange>,
+}
+
+/// A chord starting on a beat, `None` where the notes don't make one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChordChange {
+    /// Bar the chord starts in, from 0.
+    pub bar: usize,
+    /// Beat of the bar it starts on, from 0.
+    pub beat: usize,
+    pub symbol: Option<ChordSymbol>,
+}
+
+/// The chords out of `chords` played in the Standard MIDI File `data`, named a beat at a time
+/// with [`identify`] from the notes held through at least a quarter of the beat, lowest in the
+/// bass. Drums aren't counted. MIDI files timed in seconds rather than beats are read a half
+/// second to the beat.
+pub fn midi_chords(data: &[u8], chords: &[Chord]) -> Result<ChordSheet, ParseError> {
+    let smf =
+        Smf::parse(data).map_err(|e| ParseError::InvalidMidi(e.kind().message().to_string()))?;
+    let beat = match smf.header.timing {
+        Timing::Metrical(ticks) => u64::from(ticks.as_int()),
+        Timing::Timecode(fps, subframes) => (fps.as_f32() * f32::from(subframes) / 2.0) as u64,
+    }
+    .max(1);
+    let mut beats_per_bar = None;
+    // (start, end, pitch) in ticks
+    let mut notes = Vec::new();
+    for track in &smf.tracks {
+        let mut now = 0;
+        // when each key held on each channel went down
+        let mut held = vec![[None; 128]; 16];
+        for event in track {
+            now += u64::from(event.delta.as_int());
+            let (channel, key, on) = match event.kind {
+                TrackEventKind::Meta(MetaMessage::TimeSignature(numerator, ..)) => {
+                    beats_per_bar.get_or_insert(usize::from(numerator.max(1)));
+                    continue;
+                }
+                TrackEventKind::Midi { channel, message } => match message {
+                    MidiMessage::NoteOn { key, vel } => (channel.as_int(), key.as_int(), vel > 0),
+                    MidiMessage::NoteOff { key, .. } => (channel.as_int(), key.as_int(), false),
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            if channel == DRUMS {
+                continue;
+            }
+            let down = &mut held[usize::from(channel)][usize::from(key)];
+            if on {
+                down.get_or_insert(now);
+            } else if let Some(start) = down.take() {
+                notes.push((start, now, Pitch(key)));
+            }
+        }
+    }
+    let end = notes.iter().map(|&(_, end, _)| end).max().unwrap_or(0);
+    let beats = end.div_ceil(beat) as usize;
+    let beats_per_bar = beats_per_bar.unwrap_or(4);
+
+    let mut changes: Vec<ChordChange> = Vec::new();
+    for b in 0..beats {
+        let (from, to) = (b as u64 * beat, (b as u64 + 1) * beat);
+        let sounding: Vec<Pitch> = notes
+            .iter()
+            .filter(|&&(start, end, _)| end.min(to).saturating_sub(start.max(from)) * 4 >= beat)
+            .map(|&(_, _, pitch)| pitch)
+            .collect();
+        let symbol = sounding.iter().min().and_then(|bass| {
+            let set: NoteSet = sounding.iter().map(|p| p.note()).collect();
+            identify(set, bass.note(), chords).first().copied()
+        });
+        if changes.last().is_none_or(|c| c.symbol != symbol) {
+            changes.push(ChordChange {
+                bar: b / beats_per_bar,
+                beat: b % beats_per_bar,
+                symbol,
+            });
+        }
+    }
+    Ok(ChordSheet {
+        beats_per_bar,
+        bars: beats.div_ceil(beats_per_bar),
+        changes,
+    })
+}
+
+impl ChordSheet {
+    /// Parse a chart of bars like the sheet is displayed as, `| C | Am7 | Dm7 G7 | % |`, with
+    /// `%` for a bar played like the one before and N.C. for no chord, out of `chords` like
+    /// [`ChordSymbol::parse_in`]. The bars are in 4/4, the chords of each spread evenly over it.
+    pub fn parse_in(s: &str, chords: &[Chord]) -> Result<ChordSheet, ParseError> {
+        let beats_per_bar = 4;
+        let mut bars: Vec<Vec<Option<ChordSymbol>>> = Vec::new();
+        for bar in s.split('|').map(str::trim).filter(|b| !b.is_empty()) {
+            let symbols = match bar {
+                "%" => bars
+                    .last()
+                    .cloned()
+                    .ok_or_else(|| ParseError::InvalidChart(bar.to_string()))?,
+                _ => bar
+                    .split_whitespace()
+                    .map(|c| match c {
+                        "N.C." => Ok(None),
+                        _ => ChordSymbol::parse_in(c, chords).map(Some),
+                    })
+                    .collect::<Result<_, _>>()?,
+            };
+            if symbols.len() > beats_per_bar {
+                return Err(ParseError::InvalidChart(bar.to_string()));
+            }
+            bars.push(symbols);
+        }
+        let mut changes: Vec<ChordChange> = Vec::new();
+        for (bar, symbols) in bars.iter().enumerate() {
+            for (i, &symbol) in symbols.iter().enumerate() {
+                if changes.last().is_none_or(|c| c.symbol != symbol) {
+                    changes.push(ChordChange {
+                        bar,
+                        beat: i * beats_per_bar / symbols.len(),
+                        symbol,
+                    });
+                }
+            }
+        }
+        Ok(ChordSheet {
+            beats_per_bar,
+            bars: bars.len(),
+            changes,
+        })
+    }
+
+    /// The chords of each bar, starting with the one held over from the bar before if it
+    /// doesn't change on the first beat.
+    pub fn bars(&self) -> Vec<Vec<Option<ChordSymbol>>> {
+        let mut changes = self.changes.iter().peekable();
+        let mut current = None;
+        (0..self.bars)
+            .map(|bar| {
+                let mut chords = Vec::new();
+                if changes.peek().is_none_or(|c| c.bar > bar || c.beat > 0) {
+                    chords.push(current);
+                }
+                while let Some(change) = changes.next_if(|c| c.bar == bar) {
+                    current = change.symbol;
+                    chords.push(current);
+                }
+                chords
+            })
+            .collect()
+    }
+}
+
+/// The sheet as a chart of bars, four to a line like `| C | Am | F G | % |`, with `%` for a bar
+/// played like the one before and N.C. where there's no chord.
+impl fmt::Display for ChordSheet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bars = self.bars();
+        for (i, chords) in bars.iter().enumerate() {
+            if i % 4 == 0 && i > 0 {
+                writeln!(f, "|")?;
+            }
+            if i > 0 && bars[i - 1] == *chords {
+                write!(f, "| % ")?;
+            } else {
+                let names: Vec<String> = chords
+                    .iter()
+                    .map(|c| c.map_or("N.C.".to_string(), |s| s.to_string()))
+                    .collect();
+                write!(f, "| {} ", names.join(" "))?;
+            }
+        }
+        if !bars.is_empty() {
+            write!(f, "|")?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+//! Fretting several strings with one finger laid across them.
+
+use crate::Finger;
+use serde::Serialize;
+use std::ops::RangeInclusive;
+
+/// A finger laid flat across neighbouring strings at the lowest fretted fret of a fingering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Barre {
+    /// Fret the barre is held at.
+    pub fret: u8,
+    /// Strings under the barre, numbered from 1 for the highest like [`Options::strings`](crate::Options::strings).
+    pub strings: RangeInclusive<u8>,
+    /// Whether the barre covers every string of the instrument.
+    pub full: bool,
+}
+
+impl Barre {
+    /// The barre `fingering` can be played with, if two or more strings are fretted at its lowest
+    /// fretted fret. Every string between them has to be fretted at that fret or above, an open
+    /// or muted string under the finger would sound the barred note instead.
+    pub fn of(fingering: &[Finger]) -> Option<Barre> {
+        let fret = fingering
+            .iter()
+            .filter_map(|f| f.0)
+            .filter(|&x| x > 0)
+            .min()?;
+        let lo = fingering.iter().position(|f| f.0 == Some(fret))?;
+        let hi = fingering.iter().rposition(|f| f.0 == Some(fret))?;
+        let held = fingering[lo..=hi]
+            .iter()
+            .all(|f| f.0.is_some_and(|x| x >= fret));
+        if lo == hi || !held {
+            return None;
+        }
+        // string 1 is the last one in the fingering
+        let number = |i: usize| (fingering.len() - i) as u8;
+        Some(Barre {
+            fret,
+            strings: number(hi)..=number(lo),
+            full: lo == 0 && hi == fingering.len() - 1,
+        })
+    }
+}
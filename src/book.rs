@@ -0,0 +1,143 @@
+//! Typesetting chord books to print.
+
+use crate::diagram::{diagram_size, draw_diagram, Anchor};
+use crate::pdf::{Font, Page, Pdf, A4};
+use crate::Voicing;
+
+/// Space around the printed part of each page.
+const PAGE_MARGIN: f32 = 50.0;
+/// Height of a chord's name line above its diagrams.
+const CHORD_HEADING: f32 = 24.0;
+/// Distance between the lines of the index.
+const INDEX_LINE: f32 = 14.0;
+/// Columns of the index on each page.
+const INDEX_COLUMNS: usize = 3;
+
+/// How a chord book is laid out.
+#[derive(Debug, Clone)]
+pub struct BookLayout {
+    /// Printed on the title page.
+    pub title: String,
+    /// Printed under the title, like the instrument and tuning.
+    pub subtitle: String,
+    /// Chords on each page, one row of diagrams for each.
+    pub chords_per_page: usize,
+    /// Most diagrams in the row of a chord, of its first voicings.
+    pub voicings_per_chord: usize,
+}
+
+impl Default for BookLayout {
+    fn default() -> Self {
+        BookLayout {
+            title: "Chord Dictionary".to_string(),
+            subtitle: String::new(),
+            chords_per_page: 6,
+            voicings_per_chord: 4,
+        }
+    }
+}
+
+/// A chord to put in a chord book.
+#[derive(Debug, Clone)]
+pub struct BookChord<'a> {
+    /// Its symbol, like `Cmaj7`.
+    pub name: String,
+    /// Its notes, printed after the name.
+    pub notes: Vec<String>,
+    /// Its voicings, best first.
+    pub voicings: &'a [Voicing],
+}
+
+/// A PDF chord book of `chords`: a title page, then pages of chords in the order given with a
+/// row of diagrams of each, then an index of the pages the chords are on, alphabetically.
+pub fn chord_book(chords: &[BookChord], layout: &BookLayout) -> Vec<u8> {
+    let per_page = layout.chords_per_page.max(1);
+    let columns = layout.voicings_per_chord.max(1);
+    let mut pdf = Pdf::new(&layout.title);
+
+    let mut title = Page::new();
+    let middle = A4.0 / 2.0;
+    title.text_in(
+        Font::Bold,
+        (middle, 300.0),
+        32.0,
+        Anchor::Middle,
+        &layout.title,
+    );
+    title.text_in(
+        Font::Regular,
+        (middle, 340.0),
+        14.0,
+        Anchor::Middle,
+        &layout.subtitle,
+    );
+    pdf.push(title);
+
+    // every diagram at the same size, the largest that fits all of them in their rows
+    let width = A4.0 - 2.0 * PAGE_MARGIN;
+    let row_height = (A4.1 - 2.0 * PAGE_MARGIN) / per_page as f32;
+    let (most_wide, most_high) = chords
+        .iter()
+        .flat_map(|c| c.voicings.iter().take(columns))
+        .map(diagram_size)
+        .fold((1.0f32, 1.0f32), |(w, h), (x, y)| (w.max(x), h.max(y)));
+    let scale = (width / columns as f32 / most_wide)
+        .min((row_height - CHORD_HEADING) / most_high)
+        .min(1.0);
+
+    let mut index = Vec::new();
+    for page_chords in chords.chunks(per_page) {
+        let mut page = Page::new();
+        for (row, chord) in page_chords.iter().enumerate() {
+            let top = PAGE_MARGIN + row as f32 * row_height;
+            let heading = (PAGE_MARGIN, top + 14.0);
+            page.text_in(Font::Bold, heading, 14.0, Anchor::Start, &chord.name);
+            let name_width = Font::Bold.width(&chord.name, 14.0);
+            let notes = format!("({})", chord.notes.join(" "));
+            let at = (PAGE_MARGIN + name_width + 8.0, top + 14.0);
+            page.text_in(Font::Regular, at, 10.0, Anchor::Start, &notes);
+            if chord.voicings.is_empty() {
+                let at = (PAGE_MARGIN, top + CHORD_HEADING + 14.0);
+                page.text_in(Font::Regular, at, 10.0, Anchor::Start, "No voicings");
+            }
+            for (i, v) in chord.voicings.iter().take(columns).enumerate() {
+                let at = (
+                    PAGE_MARGIN + i as f32 * most_wide * scale,
+                    top + CHORD_HEADING,
+                );
+                page.placed(at, scale, |page| draw_diagram(page, v, ""));
+            }
+            index.push((chord.name.as_str(), pdf.len() + 1));
+        }
+        page_number(&mut page, pdf.len() + 1);
+        pdf.push(page);
+    }
+
+    index.sort_by(|a, b| a.0.cmp(b.0));
+    let first_line = PAGE_MARGIN + 40.0;
+    let lines = ((A4.1 - PAGE_MARGIN - first_line) / INDEX_LINE) as usize;
+    let column_width = width / INDEX_COLUMNS as f32;
+    for (n, entries) in index.chunks(lines * INDEX_COLUMNS).enumerate() {
+        let mut page = Page::new();
+        if n == 0 {
+            let at = (PAGE_MARGIN, PAGE_MARGIN + 20.0);
+            page.text_in(Font::Bold, at, 20.0, Anchor::Start, "Index");
+        }
+        for (i, (name, number)) in entries.iter().enumerate() {
+            let x = PAGE_MARGIN + (i / lines) as f32 * column_width;
+            let y = first_line + (i % lines) as f32 * INDEX_LINE;
+            page.text_in(Font::Regular, (x, y), 10.0, Anchor::Start, name);
+            let at = (x + column_width - 20.0, y);
+            page.text_in(Font::Regular, at, 10.0, Anchor::End, &number.to_string());
+        }
+        page_number(&mut page, pdf.len() + 1);
+        pdf.push(page);
+    }
+    pdf.finish()
+}
+
+/// Number `page` at the bottom.
+fn page_number(page: &mut Page, number: usize) {
+    let at = (A4.0 / 2.0, A4.1 - PAGE_MARGIN / 2.0);
+    page.text_in(Font::Regular, at, 9.0, Anchor::Middle, &number.to_string());
+}
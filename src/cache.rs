@@ -0,0 +1,186 @@
+//! Keeping generated voicings on disk between runs.
+
+use crate::{
+    generate_voicings, rebuild_voicings, Chord, Finger, Fingering, Note, Options, Pitch, Voicing,
+};
+use std::{
+    collections::hash_map::DefaultHasher,
+    env, fs,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::OnceLock,
+    time::SystemTime,
+};
+
+/// Voicings generated before, stored as one file of fingerings for each chord and settings, so
+/// generating them again with the same tuning, options and build of the program only has to read
+/// them back. The cache is best effort: files that can't be read or written are regenerated.
+///
+/// Each build of the program keeps its files in a directory of its own, and the first one it
+/// writes removes the others', which it can't read anyway, so the cache doesn't grow with every
+/// rebuild.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    /// Set once the other builds' files have been removed.
+    pruned: OnceLock<()>,
+}
+
+impl Cache {
+    /// A cache kept in `dir`, created when it's first written to.
+    pub fn new(dir: impl Into<PathBuf>) -> Cache {
+        Cache {
+            dir: dir.into(),
+            pruned: OnceLock::new(),
+        }
+    }
+
+    /// The cache in the user's cache directory, `$XDG_CACHE_HOME/chord-generator` or
+    /// `~/.cache/chord-generator`. `None` if there's no home directory to put it in.
+    pub fn user() -> Option<Cache> {
+        let base = env::var_os("XDG_CACHE_HOME")
+            .filter(|d| !d.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+        Some(Cache::new(base.join("chord-generator")))
+    }
+
+    /// Like [`generate_voicings`], but reading the voicings from the cache when they've been
+    /// generated with the same arguments before, and storing them otherwise.
+    pub fn generate_voicings(
+        &self,
+        root: Note,
+        chord: Chord,
+        t: &[Pitch],
+        options: &Options,
+    ) -> Vec<Voicing> {
+        let build_dir = self.dir.join(build());
+        let path = build_dir.join(format!("{:016x}", key(root, chord, t, options)));
+        let cached = fs::read_to_string(&path).ok().and_then(|s| parse(&s));
+        if let Some(fingerings) = cached {
+            return rebuild_voicings(root, chord, t, options, fingerings);
+        }
+        let voicings = generate_voicings(root, chord, t, options);
+        self.pruned.get_or_init(|| self.prune());
+        // written under another name first, so other runs never read half of it
+        let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+        let stored = fs::create_dir_all(&build_dir)
+            .and_then(|()| fs::write(&tmp, format(&voicings)))
+            .and_then(|()| fs::rename(&tmp, &path));
+        if stored.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        voicings
+    }
+
+    /// Remove everything in the cache but the running build's directory, along with the files
+    /// earlier versions kept straight in the cache.
+    fn prune(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let build = build();
+        for entry in entries.flatten() {
+            if entry.file_name() == build.as_str() {
+                continue;
+            }
+            let path = entry.path();
+            let _ = match entry.file_type() {
+                Ok(kind) if kind.is_dir() => fs::remove_dir_all(path),
+                _ => fs::remove_file(path),
+            };
+        }
+    }
+}
+
+/// When the running program was built, as a program rebuilt from changed code may generate
+/// different voicings without a new version number.
+fn built() -> Option<SystemTime> {
+    static BUILT: OnceLock<Option<SystemTime>> = OnceLock::new();
+    *BUILT.get_or_init(|| env::current_exe().ok()?.metadata().ok()?.modified().ok())
+}
+
+/// Names the directory of the running build, from its version and when it was built.
+fn build() -> String {
+    let mut hasher = DefaultHasher::new();
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    built().hash(&mut hasher);
+    format!("build-{:016x}", hasher.finish())
+}
+
+/// Identifies everything else the voicings depend on, the chord's formula too as custom qualities
+/// can be redefined under the same name.
+fn key(root: Note, chord: Chord, t: &[Pitch], options: &Options) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    format!(
+        "{:?} {:?} {:?} {:?} {:?}",
+        root,
+        chord,
+        chord.tones(),
+        t,
+        options
+    )
+    .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// One voicing per line, its frets from the lowest string separated by spaces, `x` for muted.
+fn format(voicings: &[Voicing]) -> String {
+    voicings
+        .iter()
+        .map(|v| {
+            let frets: Vec<String> = v
+                .frets
+                .iter()
+                .map(|f| f.0.map_or("x".to_string(), |x| x.to_string()))
+                .collect();
+            frets.join(" ") + "\n"
+        })
+        .collect()
+}
+
+/// The fingerings of a file written by [`format`], `None` if it's been damaged.
+fn parse(s: &str) -> Option<Vec<Fingering>> {
+    s.lines()
+        .map(|line| {
+            line.split(' ')
+                .map(|f| match f {
+                    "x" => Some(Finger(None)),
+                    x => x.parse().ok().map(|x| Finger(Some(x))),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DEFAULT_TUNING;
+
+    #[test]
+    fn removes_other_builds_entries() {
+        let dir = env::temp_dir().join(format!("chord-generator-cache-{}", std::process::id()));
+        let stale = dir.join("build-0000000000000000");
+        fs::create_dir_all(&stale).unwrap();
+        fs::write(stale.join("0123456789abcdef"), "x 3 2 0 1 0\n").unwrap();
+        fs::write(dir.join("0123456789abcdef"), "x 3 2 0 1 0\n").unwrap();
+
+        let cache = Cache::new(&dir);
+        let options = Options::default();
+        let generated = cache.generate_voicings(Note::C, Chord::MAJOR, DEFAULT_TUNING, &options);
+        let names: Vec<String> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, [build()]);
+        assert_eq!(fs::read_dir(dir.join(build())).unwrap().count(), 1);
+
+        let read = cache.generate_voicings(Note::C, Chord::MAJOR, DEFAULT_TUNING, &options);
+        let frets = |voicings: &[Voicing]| -> Vec<Fingering> {
+            voicings.iter().map(|v| v.frets.clone()).collect()
+        };
+        assert_eq!(frets(&read), frets(&generated));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,559 @@
+//! Chord qualities, defined by interval formulas so new ones can be added at runtime.
+
+use crate::{parse_quality, Note, ParseError};
+use serde::{Serialize, Serializer};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        OnceLock,
+    },
+};
+
+/// Semitones above the root of each scale degree of the major scale, up to the 13th.
+const MAJOR_DEGREES: [u8; 13] = [0, 2, 4, 5, 7, 9, 11, 12, 14, 16, 17, 19, 21];
+
+/// Names of the intervals within an octave above the root, by semitones.
+const INTERVALS: [&str; 12] = [
+    "1", "b2", "2", "b3", "3", "4", "b5", "5", "b6", "6", "b7", "7",
+];
+
+/// One tone of a chord formula, e.g. the `b7` in `"1 3 5 b7"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tone {
+    /// Scale degree, 1 for the root up to 13.
+    pub degree: u8,
+    /// Semitones above the root, past the octave for 9ths, 11ths and 13ths.
+    pub semitones: u8,
+    /// Whether voicings may leave this tone out, written in parentheses like `(5)`.
+    pub optional: bool,
+}
+
+impl Tone {
+    /// How many semitones the tone is raised (positive) or lowered (negative) from the major
+    /// scale degree.
+    pub fn alteration(&self) -> i8 {
+        self.semitones as i8 - MAJOR_DEGREES[self.degree as usize - 1] as i8
+    }
+}
+
+impl fmt::Display for Tone {
+    /// The tone as it's written in a formula, like `b7`, without the parentheses of an optional
+    /// one.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let alteration = self.alteration();
+        let accidental = if alteration < 0 { "b" } else { "#" };
+        write!(
+            f,
+            "{}{}",
+            accidental.repeat(alteration.unsigned_abs() as usize),
+            self.degree
+        )
+    }
+}
+
+/// Parse an interval formula like `"1 b3 5 11"`, scale degrees of the major scale each with any
+/// number of `b`/`#` accidentals. Tones in parentheses, like `(5)`, may be left out of voicings.
+/// The formula has to start with the root, `1`.
+pub fn parse_formula(s: &str) -> Result<Vec<Tone>, ParseError> {
+    let invalid = || ParseError::InvalidFormula(s.to_string());
+    let mut tones = Vec::new();
+    for word in s.split(|c: char| c.is_whitespace() || c == ',') {
+        if word.is_empty() {
+            continue;
+        }
+        let (word, optional) = match word.strip_prefix('(').and_then(|w| w.strip_suffix(')')) {
+            Some(inner) => (inner, true),
+            None => (word, false),
+        };
+        let degree_at = word
+            .find(|c: char| c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let (accidentals, degree) = word.split_at(degree_at);
+        let mut shift: i32 = 0;
+        for c in accidentals.chars() {
+            match c {
+                '#' | '♯' => shift += 1,
+                'b' | '♭' => shift -= 1,
+                _ => return Err(invalid()),
+            }
+        }
+        let degree: u8 = degree.parse().map_err(|_| invalid())?;
+        if !(1..=13).contains(&degree) {
+            return Err(invalid());
+        }
+        let semitones = MAJOR_DEGREES[degree as usize - 1] as i32 + shift;
+        tones.push(Tone {
+            degree,
+            semitones: u8::try_from(semitones).map_err(|_| invalid())?,
+            optional,
+        });
+    }
+    match tones.first() {
+        Some(root) if root.semitones == 0 && !root.optional => Ok(tones),
+        _ => Err(invalid()),
+    }
+}
+
+/// How a chord quality is written and the tones that make it up.
+#[derive(Debug)]
+pub struct Quality {
+    /// Name used to key the quality in the output, e.g. `"MajorSeventh"`.
+    pub name: &'static str,
+    /// Conventional suffix in a chord symbol, e.g. `"maj7"`. Empty for major.
+    pub symbol: &'static str,
+    /// Other suffixes accepted when parsing chord symbols.
+    pub aliases: &'static [&'static str],
+    /// Interval formula, see [`parse_formula`].
+    pub formula: &'static str,
+}
+
+/// The built-in qualities.
+pub const QUALITIES: &[Quality] = &[
+    Quality {
+        name: "Major",
+        symbol: "",
+        aliases: &["M", "maj", "major"],
+        formula: "1 3 5",
+    },
+    Quality {
+        name: "Minor",
+        symbol: "m",
+        aliases: &["min", "minor", "-"],
+        formula: "1 b3 5",
+    },
+    Quality {
+        name: "Augmented",
+        symbol: "aug",
+        aliases: &["+", "+5", "#5", "aug5"],
+        formula: "1 3 #5",
+    },
+    Quality {
+        name: "Diminished",
+        symbol: "dim",
+        aliases: &["°", "o"],
+        formula: "1 b3 b5",
+    },
+    Quality {
+        name: "Seventh",
+        symbol: "7",
+        aliases: &["dom7", "dom"],
+        formula: "1 3 5 b7",
+    },
+    Quality {
+        name: "MajorSeventh",
+        symbol: "maj7",
+        aliases: &["M7", "ma7", "Maj7", "Δ7", "Δ"],
+        formula: "1 3 5 7",
+    },
+    Quality {
+        name: "MinorSeventh",
+        symbol: "m7",
+        aliases: &["min7", "-7"],
+        formula: "1 b3 5 b7",
+    },
+    Quality {
+        name: "Sus2",
+        symbol: "sus2",
+        aliases: &["2"],
+        formula: "1 2 5",
+    },
+    Quality {
+        name: "Sus4",
+        symbol: "sus4",
+        aliases: &["sus", "4"],
+        formula: "1 4 5",
+    },
+    Quality {
+        name: "MinorMajorSeventh",
+        symbol: "mmaj7",
+        aliases: &[
+            "mMaj7", "mM7", "m(maj7)", "minmaj7", "-maj7", "-Δ7", "mΔ7", "-M7",
+        ],
+        formula: "1 b3 5 7",
+    },
+    Quality {
+        name: "DiminishedSeventh",
+        symbol: "dim7",
+        aliases: &["°7", "o7"],
+        formula: "1 b3 b5 bb7",
+    },
+    Quality {
+        name: "MajorNinth",
+        symbol: "maj9",
+        aliases: &["M9", "ma9", "Maj9", "Δ9"],
+        formula: "1 3 5 7 9",
+    },
+    Quality {
+        name: "MinorNinth",
+        symbol: "m9",
+        aliases: &["min9", "-9"],
+        formula: "1 b3 5 b7 9",
+    },
+    Quality {
+        name: "AddNinth",
+        symbol: "add9",
+        aliases: &["add2", "(add9)"],
+        formula: "1 3 5 9",
+    },
+    Quality {
+        name: "AddEleventh",
+        symbol: "add11",
+        aliases: &["add4", "(add11)"],
+        formula: "1 3 5 11",
+    },
+    Quality {
+        name: "MinorSixth",
+        symbol: "m6",
+        aliases: &["min6", "-6"],
+        formula: "1 b3 5 6",
+    },
+    Quality {
+        name: "MajorSixth",
+        symbol: "6",
+        aliases: &["M6", "maj6"],
+        formula: "1 3 5 6",
+    },
+    Quality {
+        name: "AddSixthAddNinth",
+        symbol: "6/9",
+        aliases: &["69", "6add9", "6(add9)"],
+        formula: "1 3 5 6 9",
+    },
+    // power chord
+    Quality {
+        name: "Five",
+        symbol: "5",
+        aliases: &["(no3)", "no3", "power"],
+        formula: "1 5",
+    },
+    Quality {
+        name: "SeventhFlatNinth",
+        symbol: "7b9",
+        aliases: &["7♭9", "7(b9)", "7-9"],
+        formula: "1 3 5 b7 b9",
+    },
+    Quality {
+        name: "SeventhSharpNinth",
+        symbol: "7#9",
+        aliases: &["7♯9", "7(#9)", "7+9"],
+        formula: "1 3 5 b7 #9",
+    },
+    Quality {
+        name: "SeventhFlatFifth",
+        symbol: "7b5",
+        aliases: &["7♭5", "7(b5)", "7-5"],
+        formula: "1 3 b5 b7",
+    },
+    Quality {
+        name: "SeventhSharpFifth",
+        symbol: "7#5",
+        aliases: &["7♯5", "7(#5)", "7+5", "+7", "aug7"],
+        formula: "1 3 #5 b7",
+    },
+    Quality {
+        name: "HalfDiminished",
+        symbol: "m7b5",
+        aliases: &["ø", "ø7", "m7♭5", "m7(b5)", "min7b5", "-7b5", "-7♭5"],
+        formula: "1 b3 b5 b7",
+    },
+    Quality {
+        name: "Ninth",
+        symbol: "9",
+        aliases: &["dom9"],
+        formula: "1 3 5 b7 9",
+    },
+    // the third clashes with the 11th, so it's usually left out
+    Quality {
+        name: "Eleventh",
+        symbol: "11",
+        aliases: &["dom11"],
+        formula: "1 (3) 5 b7 9 11",
+    },
+    // the 11th is left out of 13th chords, it clashes with the third
+    Quality {
+        name: "Thirteenth",
+        symbol: "13",
+        aliases: &["dom13"],
+        formula: "1 3 5 b7 9 13",
+    },
+];
+
+/// Identifies qualities defined at runtime, numbered after the built-in ones.
+static NEXT_CUSTOM: AtomicUsize = AtomicUsize::new(QUALITIES.len());
+
+/// The [`Chord::tones`] of each of the built-in qualities, parsed the first time they're needed.
+static BUILTIN_TONES: OnceLock<Vec<Vec<Tone>>> = OnceLock::new();
+
+/// The tones of `formula`, which has been checked already, with the omission rules of
+/// [`Chord::tones`] applied.
+fn tones_of(formula: &str) -> Vec<Tone> {
+    let mut tones = parse_formula(formula).unwrap();
+    let count = tones.len();
+    let seventh = tones.iter().any(|t| t.degree == 7);
+    for tone in &mut tones {
+        let rule = match tone.degree {
+            1 => count >= 6,
+            5 => (count >= 5 || (count == 4 && seventh)) && tone.alteration() == 0,
+            _ => false,
+        };
+        tone.optional |= rule;
+    }
+    tones
+}
+
+/// A chord quality, independent of its root. Either one of the built-in [`QUALITIES`] or one
+/// defined with [`Chord::custom`]. Qualities compare in the order they were defined, built-in
+/// ones first.
+#[derive(Clone, Copy)]
+pub struct Chord {
+    id: usize,
+    quality: &'static Quality,
+    /// Tones of a custom quality, the built-in ones are in [`BUILTIN_TONES`].
+    tones: Option<&'static [Tone]>,
+}
+
+impl Chord {
+    pub const MAJOR: Chord = Chord::builtin(0);
+    pub const MINOR: Chord = Chord::builtin(1);
+    pub const AUGMENTED: Chord = Chord::builtin(2);
+    pub const DIMINISHED: Chord = Chord::builtin(3);
+    pub const SEVENTH: Chord = Chord::builtin(4);
+    pub const MAJOR_SEVENTH: Chord = Chord::builtin(5);
+    pub const MINOR_SEVENTH: Chord = Chord::builtin(6);
+    pub const SUS2: Chord = Chord::builtin(7);
+    pub const SUS4: Chord = Chord::builtin(8);
+    pub const MINOR_MAJOR_SEVENTH: Chord = Chord::builtin(9);
+    pub const DIMINISHED_SEVENTH: Chord = Chord::builtin(10);
+    pub const MAJOR_NINTH: Chord = Chord::builtin(11);
+    pub const MINOR_NINTH: Chord = Chord::builtin(12);
+    pub const ADD_NINTH: Chord = Chord::builtin(13);
+    pub const ADD_ELEVENTH: Chord = Chord::builtin(14);
+    pub const MINOR_SIXTH: Chord = Chord::builtin(15);
+    pub const MAJOR_SIXTH: Chord = Chord::builtin(16);
+    pub const ADD_SIXTH_ADD_NINTH: Chord = Chord::builtin(17);
+    pub const FIVE: Chord = Chord::builtin(18);
+    pub const SEVENTH_FLAT_NINTH: Chord = Chord::builtin(19);
+    pub const SEVENTH_SHARP_NINTH: Chord = Chord::builtin(20);
+    pub const SEVENTH_FLAT_FIFTH: Chord = Chord::builtin(21);
+    pub const SEVENTH_SHARP_FIFTH: Chord = Chord::builtin(22);
+    pub const HALF_DIMINISHED: Chord = Chord::builtin(23);
+    pub const NINTH: Chord = Chord::builtin(24);
+    pub const ELEVENTH: Chord = Chord::builtin(25);
+    pub const THIRTEENTH: Chord = Chord::builtin(26);
+
+    const fn builtin(id: usize) -> Chord {
+        Chord {
+            id,
+            quality: &QUALITIES[id],
+            tones: None,
+        }
+    }
+
+    /// All the built-in qualities, in the order of [`QUALITIES`].
+    pub fn builtins() -> impl Iterator<Item = Chord> {
+        (0..QUALITIES.len()).map(Chord::builtin)
+    }
+
+    /// Define a new quality named `name` from an interval formula like `"1 b3 5 11"`, see
+    /// [`parse_formula`]. The name doubles as its chord symbol suffix.
+    ///
+    /// The definition lives for the rest of the program.
+    pub fn custom(name: &str, formula: &str) -> Result<Chord, ParseError> {
+        parse_formula(formula)?;
+        let tones = Box::leak(tones_of(formula).into_boxed_slice());
+        let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+        let quality = Box::leak(Box::new(Quality {
+            name,
+            symbol: name,
+            aliases: &[],
+            formula: Box::leak(formula.to_string().into_boxed_str()),
+        }));
+        Ok(Chord {
+            id: NEXT_CUSTOM.fetch_add(1, Ordering::Relaxed),
+            quality,
+            tones: Some(tones),
+        })
+    }
+
+    /// Name of the quality, e.g. `"MajorSeventh"`.
+    pub fn name(&self) -> &'static str {
+        self.quality.name
+    }
+
+    /// Conventional suffix for this quality in a chord symbol, e.g. `"maj7"`. Empty for major.
+    pub fn symbol(&self) -> &'static str {
+        self.quality.symbol
+    }
+
+    /// Other suffixes this quality is written with.
+    pub fn aliases(&self) -> &'static [&'static str] {
+        self.quality.aliases
+    }
+
+    /// The tones of this chord, root first, with the omission rules applied: besides the tones in
+    /// parentheses, seventh chords and chords of five or more tones may leave out a perfect fifth,
+    /// like the open C7 x32310 does, and chords of six or more the root too, as there usually
+    /// aren't enough strings for all of them.
+    pub fn tones(&self) -> &'static [Tone] {
+        self.tones.unwrap_or_else(|| {
+            let builtins = BUILTIN_TONES
+                .get_or_init(|| QUALITIES.iter().map(|q| tones_of(q.formula)).collect());
+            &builtins[self.id]
+        })
+    }
+
+    /// The notes making up this chord when built on `root`, root first.
+    pub fn notes(&self, root: Note) -> Vec<Note> {
+        self.tones().iter().map(|t| root + t.semitones).collect()
+    }
+
+    /// The tone `note` is in this chord built on `root`, if it's one of them.
+    pub fn tone_of(&self, root: Note, note: Note) -> Option<Tone> {
+        self.tones()
+            .iter()
+            .find(|t| root + t.semitones == note)
+            .copied()
+    }
+
+    /// The interval `note` is above `root` in this chord: its tone as written in the formula,
+    /// like `b3` or `9`, or the simple interval for a note outside the chord like a slash
+    /// chord's bass.
+    pub fn interval(&self, root: Note, note: Note) -> String {
+        match self.tone_of(root, note) {
+            Some(tone) => tone.to_string(),
+            None => {
+                let semitones = (0..12).find(|&i| root + i == note).unwrap_or(0);
+                INTERVALS[semitones as usize].to_string()
+            }
+        }
+    }
+
+    /// Chord notes a voicing may leave out, see [`Chord::tones`].
+    pub fn optional_notes(&self, root: Note) -> Vec<Note> {
+        self.tones()
+            .iter()
+            .filter(|t| t.optional)
+            .map(|t| root + t.semitones)
+            .collect()
+    }
+
+    /// The root, third and seventh of this chord built on `root`, the notes of a shell voicing.
+    /// Sixth chords use their sixth instead of a seventh. `None` for chords without a third or
+    /// either of them.
+    pub fn shell_notes(&self, root: Note) -> Option<Vec<Note>> {
+        let tones = self.tones();
+        let find = |degree| tones.iter().find(|t| t.degree == degree);
+        let third = find(3)?;
+        let seventh = find(7).or_else(|| find(6))?;
+        Some(vec![root, root + third.semitones, root + seventh.semitones])
+    }
+
+    /// The tones of a rootless voicing of this chord, for playing with a bassist: every tone but
+    /// the root, with only an unaltered fifth optional, so the third, seventh (or sixth) and any
+    /// extensions have to be played. `None` for chords without a third or either of them.
+    pub fn rootless_tones(&self) -> Option<Vec<Tone>> {
+        let tones: Vec<Tone> = self
+            .tones()
+            .iter()
+            .filter(|t| t.semitones % 12 != 0)
+            .map(|&t| Tone {
+                optional: t.degree == 5 && t.alteration() == 0,
+                ..t
+            })
+            .collect();
+        let has = |degree| tones.iter().any(|t| t.degree == degree);
+        (has(3) && (has(7) || has(6))).then_some(tones)
+    }
+
+    /// Fewest strings a voicing of this quality needs to play, for the qualities that sound
+    /// complete on fewer strings than [`Options::min_strings`](crate::Options::min_strings)
+    /// asks for, like power chords.
+    pub fn min_strings(&self) -> Option<usize> {
+        (self.tones().len() < 3).then_some(2)
+    }
+}
+
+impl PartialEq for Chord {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Chord {}
+
+impl Hash for Chord {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+impl PartialOrd for Chord {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Chord {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl fmt::Debug for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for Chord {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(self.name())
+    }
+}
+
+impl FromStr for Chord {
+    type Err = ParseError;
+
+    /// Parse a quality suffix like `"maj7"`, `"m"` or `"Δ7"`, see [`parse_quality`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_quality(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_voicings, parse_fret_string, Note, Options, DEFAULT_TUNING};
+
+    fn optional(chord: Chord) -> Vec<u8> {
+        chord
+            .tones()
+            .iter()
+            .filter(|t| t.optional)
+            .map(|t| t.degree)
+            .collect()
+    }
+
+    #[test]
+    fn seventh_chords_may_leave_out_the_fifth() {
+        assert_eq!(optional(Chord::SEVENTH), [5]);
+        assert_eq!(optional(Chord::MAJOR_SEVENTH), [5]);
+        assert_eq!(optional(Chord::MINOR_SEVENTH), [5]);
+        assert!(optional(Chord::MAJOR).is_empty());
+        assert!(optional(Chord::MAJOR_SIXTH).is_empty());
+        // an altered fifth is what makes the chord
+        assert!(optional(Chord::HALF_DIMINISHED).is_empty());
+        assert!(optional(Chord::SEVENTH_FLAT_FIFTH).is_empty());
+    }
+
+    #[test]
+    fn generates_the_open_c7() {
+        let c7 = parse_fret_string("x32310").unwrap();
+        let voicings =
+            generate_voicings(Note::C, Chord::SEVENTH, DEFAULT_TUNING, &Options::default());
+        assert!(voicings.iter().any(|v| v.frets == c7));
+    }
+}
@@ -0,0 +1,74 @@
+//! Describing voicings the way the chords-db project's JSON does, for the React components
+//! drawing its chords.
+
+use crate::{Chord, Note, Voicing};
+use serde::Serialize;
+
+/// A position of a chord in chords-db, one voicing of it. Strings run from the lowest like
+/// [`Voicing::frets`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChordsDbPosition {
+    /// Fret held on each string counting from 1 at the `base_fret`, 0 for open and -1 for muted.
+    pub frets: Vec<i8>,
+    /// Finger holding each string, from 1 for the index finger, 0 for none or the thumb.
+    pub fingers: Vec<u8>,
+    /// The fret the chart starts at.
+    pub base_fret: u8,
+    /// Frets the barres are held at, counted like `frets`.
+    pub barres: Vec<u8>,
+    /// Whether the barre covers every string, which chords-db draws like a capo.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub capo: bool,
+    /// The pitches sounded as MIDI note numbers, from the lowest string.
+    pub midi: Vec<u8>,
+}
+
+impl ChordsDbPosition {
+    /// The position of `v`.
+    pub fn of(v: &Voicing) -> ChordsDbPosition {
+        let fingers = match &v.fingers {
+            Some(fingers) => fingers.iter().map(|f| f.unwrap_or(0)).collect(),
+            None => vec![0; v.frets.len()],
+        };
+        ChordsDbPosition {
+            frets: v.relative_frets.iter().map(|&f| i8::from(f)).collect(),
+            fingers,
+            base_fret: v.base_fret,
+            barres: v.barre.iter().map(|b| b.fret - v.base_fret + 1).collect(),
+            capo: v.barre.as_ref().is_some_and(|b| b.full),
+            midi: v.pitches.iter().flatten().map(|p| p.0).collect(),
+        }
+    }
+}
+
+/// The name chords-db gives the key of `root`, which spells the black keys as in
+/// [`key_spelling`](crate::key_spelling) except for C# and F#.
+pub fn chords_db_key(root: Note) -> &'static str {
+    match root {
+        Note::CSharp => "C#",
+        Note::DSharp => "Eb",
+        Note::FSharp => "F#",
+        Note::GSharp => "Ab",
+        Note::ASharp => "Bb",
+        _ => root.name(),
+    }
+}
+
+/// The suffix chords-db names `chord` by, like `m7`, followed by the bass note of a slash chord
+/// like `m/E`. Most qualities are named by their own symbol.
+pub fn chords_db_suffix(chord: Chord, bass: Option<&str>) -> String {
+    let suffix = match chord.name() {
+        "Major" if bass.is_some() => "",
+        "Major" => "major",
+        "Minor" if bass.is_some() => "m",
+        "Minor" => "minor",
+        "AddSixthAddNinth" => "69",
+        "SeventhSharpFifth" => "aug7",
+        _ => chord.symbol(),
+    };
+    match bass {
+        Some(bass) => format!("{}/{}", suffix, bass),
+        None => suffix.to_string(),
+    }
+}
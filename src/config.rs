@@ -0,0 +1,44 @@
+//! Settings read from a TOML config file.
+
+use crate::{parse_quality, Chord, ParseError, Weights};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// User settings, read from a TOML file like
+///
+/// ```toml
+/// [chords]
+/// madd11 = "1 b3 5 11"
+///
+/// [weights]
+/// open = 20
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Extra chord qualities by name, as interval formulas, see [`parse_formula`](crate::parse_formula).
+    pub chords: BTreeMap<String, String>,
+    /// Scoring weights, the defaults for the ones left out.
+    pub weights: Weights,
+}
+
+impl Config {
+    /// Parse the contents of a config file.
+    pub fn from_toml(s: &str) -> Result<Config, ParseError> {
+        toml::from_str(s).map_err(|e| ParseError::InvalidConfig(e.to_string()))
+    }
+
+    /// Define the extra chord qualities, in order of their names. Names already taken by a
+    /// built-in quality or one of its aliases are rejected.
+    pub fn chords(&self) -> Result<Vec<Chord>, ParseError> {
+        self.chords
+            .iter()
+            .map(|(name, formula)| {
+                if name.is_empty() || parse_quality(name).is_ok() {
+                    return Err(ParseError::ChordExists(name.clone()));
+                }
+                Chord::custom(name, formula)
+            })
+            .collect()
+    }
+}
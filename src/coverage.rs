@@ -0,0 +1,327 @@
+//! Finding out why a chord has no voicings, and relaxing the filters until it has some.
+
+use crate::{Chord, Finger, Note, Options, Pitch, Voicing, Voicings};
+use std::fmt;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// A filter in [`Options`] that rejects fingerings, in the order they're applied: the ones
+/// pruning the search first, then the ones checking each fingering found, then each voicing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)]
+pub enum Filter {
+    /// [`Options::position`]
+    Position,
+    /// [`Options::compact`], within [`Options::max_span`]
+    Compact,
+    /// [`Options::contiguous`]
+    Contiguous,
+    /// [`Options::open_strings`]
+    OpenStrings,
+    /// [`Options::min_strings`]
+    MinStrings,
+    /// [`Options::max_strings`]
+    MaxStrings,
+    /// [`Options::root_in_bass`]
+    RootInBass,
+    /// [`Options::four_fingered`]
+    FourFingered,
+    /// [`Options::inversion`]
+    Inversion,
+    /// [`Options::root_string`]
+    RootString,
+    /// [`Options::drop`]
+    Drop,
+    /// [`Options::spacing`]
+    Spacing,
+    /// [`Options::max_gap`]
+    MaxGap,
+    /// [`Options::low_interval_limits`]
+    LowIntervalLimits,
+    /// [`Options::require_third`]
+    RequireThird,
+    /// [`Options::strict_doubling`]
+    StrictDoubling,
+    /// [`Options::no_unisons`]
+    NoUnisons,
+    /// [`Options::textures`] and [`Options::excluded_textures`]
+    Textures,
+    /// [`Options::max_difficulty`]
+    MaxDifficulty,
+    /// [`Options::top`]
+    Top,
+}
+
+impl Filter {
+    /// Lowercase name, e.g. `"max-difficulty"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Filter::Position => "position",
+            Filter::Compact => "max-span",
+            Filter::Contiguous => "contiguous",
+            Filter::OpenStrings => "open-strings",
+            Filter::MinStrings => "min-strings",
+            Filter::MaxStrings => "max-strings",
+            Filter::RootInBass => "root-in-bass",
+            Filter::FourFingered => "four-fingered",
+            Filter::Inversion => "inversion",
+            Filter::RootString => "root-string",
+            Filter::Drop => "drop",
+            Filter::Spacing => "voicing",
+            Filter::MaxGap => "max-gap",
+            Filter::LowIntervalLimits => "low-interval-limits",
+            Filter::RequireThird => "require-third",
+            Filter::StrictDoubling => "strict-doubling",
+            Filter::NoUnisons => "no-unisons",
+            Filter::Textures => "texture",
+            Filter::MaxDifficulty => "max-difficulty",
+            Filter::Top => "top",
+        }
+    }
+
+    /// Whether the filter rejects anything with `options`.
+    pub fn is_on(&self, options: &Options) -> bool {
+        match self {
+            Filter::Position => options.position.is_some(),
+            Filter::Compact => options.compact,
+            Filter::Contiguous => options.contiguous,
+            Filter::OpenStrings => options.open_strings.is_some(),
+            // every fingering plays at least one string
+            Filter::MinStrings => options.min_strings > 1,
+            Filter::MaxStrings => options.max_strings.is_some(),
+            Filter::RootInBass => options.root_in_bass,
+            Filter::FourFingered => options.four_fingered,
+            Filter::Inversion => options.inversion.is_some(),
+            Filter::RootString => options.root_string.is_some(),
+            Filter::Drop => options.drop.is_some(),
+            Filter::Spacing => options.spacing.is_some(),
+            Filter::MaxGap => options.max_gap.is_some(),
+            Filter::LowIntervalLimits => options.low_interval_limits,
+            Filter::RequireThird => options.require_third,
+            Filter::StrictDoubling => options.strict_doubling,
+            Filter::NoUnisons => options.no_unisons,
+            Filter::Textures => {
+                !options.textures.is_empty() || !options.excluded_textures.is_empty()
+            }
+            Filter::MaxDifficulty => options.max_difficulty.is_some(),
+            Filter::Top => options.top.is_some(),
+        }
+    }
+
+    /// `options` with the filter turned off.
+    pub fn off(&self, options: &Options) -> Options {
+        let mut options = options.clone();
+        match self {
+            Filter::Position => options.position = None,
+            Filter::Compact => options.compact = false,
+            Filter::Contiguous => options.contiguous = false,
+            Filter::OpenStrings => options.open_strings = None,
+            Filter::MinStrings => options.min_strings = 1,
+            Filter::MaxStrings => options.max_strings = None,
+            Filter::RootInBass => options.root_in_bass = false,
+            Filter::FourFingered => options.four_fingered = false,
+            Filter::Inversion => options.inversion = None,
+            Filter::RootString => options.root_string = None,
+            Filter::Drop => options.drop = None,
+            Filter::Spacing => options.spacing = None,
+            Filter::MaxGap => options.max_gap = None,
+            Filter::LowIntervalLimits => options.low_interval_limits = false,
+            Filter::RequireThird => options.require_third = false,
+            Filter::StrictDoubling => options.strict_doubling = false,
+            Filter::NoUnisons => options.no_unisons = false,
+            Filter::Textures => {
+                options.textures.clear();
+                options.excluded_textures.clear();
+            }
+            Filter::MaxDifficulty => options.max_difficulty = None,
+            Filter::Top => options.top = None,
+        }
+        options
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The filter that rejects the last voicings of `chord` built on `root` in tuning `t` with
+/// `options`, when it has none: with the filters that are on turned back on one at a time in the
+/// order they're applied, the first one leaving no voicings. `None` if the chord has voicings, or
+/// has none even with every filter off, as no fingering within [`Options::max_fret`] plays it.
+pub fn eliminated_by(root: Note, chord: Chord, t: &[Pitch], options: &Options) -> Option<Filter> {
+    first_rejecting(options, |options| {
+        Voicings::iter(root, chord, t, options).next().is_some()
+    })
+}
+
+/// The filter that rejects `fingering` of `chord` built on `root` in tuning `t` with `options`,
+/// found like [`eliminated_by`]. `None` if it's one of the chord's voicings, or isn't even with
+/// every filter off, as it doesn't play the chord.
+pub fn rejected_by(
+    root: Note,
+    chord: Chord,
+    t: &[Pitch],
+    fingering: &[Finger],
+    options: &Options,
+) -> Option<Filter> {
+    first_rejecting(options, |options| {
+        Voicings::iter(root, chord, t, options).any(|v| v.frets == fingering)
+    })
+}
+
+/// The first of the filters that are on in `options` to leave nothing `found` when they're
+/// turned back on one at a time, `None` if something is found with `options` or nothing is with
+/// every filter off.
+fn first_rejecting(options: &Options, found: impl Fn(&Options) -> bool) -> Option<Filter> {
+    if found(options) {
+        return None;
+    }
+    let on: Vec<Filter> = Filter::iter().filter(|f| f.is_on(options)).collect();
+    // the first `n` filters turned back on, the others still off
+    let relaxed = |n: usize| on[n..].iter().fold(options.clone(), |o, f| f.off(&o));
+    if !found(&relaxed(0)) {
+        return None;
+    }
+    (1..=on.len())
+        .find(|&n| !found(&relaxed(n)))
+        .map(|n| on[n - 1])
+}
+
+/// A way to let more fingerings through when a chord has no voicings, in the order
+/// [`relax_until_found`] tries them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EnumIter)]
+pub enum Relaxation {
+    /// Playing just three strings, when [`Options::min_strings`] asks for more.
+    ThreeStrings,
+    /// A fret more of stretch than [`Options::max_span`].
+    WiderSpan,
+    /// Muted strings between played ones, see [`Options::interior_mutes`].
+    InteriorMutes,
+}
+
+impl Relaxation {
+    /// Lowercase name, e.g. `"wider-span"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Relaxation::ThreeStrings => "three-strings",
+            Relaxation::WiderSpan => "wider-span",
+            Relaxation::InteriorMutes => "interior-mutes",
+        }
+    }
+
+    /// `options` relaxed this way, `None` if they already allow it.
+    pub fn relax(&self, options: &Options) -> Option<Options> {
+        let mut options = options.clone();
+        match self {
+            Relaxation::ThreeStrings if options.min_strings > 3 => options.min_strings = 3,
+            Relaxation::WiderSpan if options.compact => options.max_span += 1,
+            Relaxation::InteriorMutes if options.contiguous && !options.interior_mutes => {
+                options.interior_mutes = true
+            }
+            _ => return None,
+        }
+        Some(options)
+    }
+}
+
+impl fmt::Display for Relaxation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The voicings `generate` finds with `options`, or when there are none, with each
+/// [`Relaxation`] in turn added to the ones before until there are some, along with the
+/// relaxations that took. Nothing is relaxed if they don't turn up any voicings either.
+pub fn relax_until_found(
+    options: &Options,
+    mut generate: impl FnMut(&Options) -> Vec<Voicing>,
+) -> (Vec<Voicing>, Vec<Relaxation>) {
+    let voicings = generate(options);
+    if !voicings.is_empty() {
+        return (voicings, Vec::new());
+    }
+    let mut relaxed = options.clone();
+    let mut applied = Vec::new();
+    for relaxation in Relaxation::iter() {
+        let Some(options) = relaxation.relax(&relaxed) else {
+            continue;
+        };
+        relaxed = options;
+        applied.push(relaxation);
+        let voicings = generate(&relaxed);
+        if !voicings.is_empty() {
+            return (voicings, applied);
+        }
+    }
+    (voicings, Vec::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_fret_string, Difficulty, DEFAULT_TUNING};
+
+    #[test]
+    fn names_the_filter_leaving_a_chord_without_voicings() {
+        let options = Options {
+            max_difficulty: Some(Difficulty::Beginner),
+            root_in_bass: true,
+            ..Options::default()
+        };
+        let eliminated = |chord| eliminated_by(Note::C, chord, DEFAULT_TUNING, &options);
+        assert_eq!(eliminated(Chord::AUGMENTED), Some(Filter::MaxDifficulty));
+        assert_eq!(eliminated(Chord::MAJOR), None);
+    }
+
+    #[test]
+    fn names_the_filter_rejecting_a_fingering() {
+        let c = parse_fret_string("x32010").unwrap();
+        let rejected = |options: &Options, frets: &[Finger]| {
+            rejected_by(Note::C, Chord::MAJOR, DEFAULT_TUNING, frets, options)
+        };
+        assert_eq!(rejected(&Options::default(), &c), None);
+        let closed = Options {
+            open_strings: Some(false),
+            ..Options::default()
+        };
+        assert_eq!(rejected(&closed, &c), Some(Filter::OpenStrings));
+        // an Am, not a C
+        let am = parse_fret_string("x02210").unwrap();
+        assert_eq!(rejected(&closed, &am), None);
+    }
+
+    #[test]
+    fn turning_filters_off_lets_everything_through() {
+        let options = Options {
+            position: Some(5..=8),
+            no_unisons: true,
+            ..Options::default()
+        };
+        let on: Vec<Filter> = Filter::iter().filter(|f| f.is_on(&options)).collect();
+        assert!(on.contains(&Filter::Position) && on.contains(&Filter::NoUnisons));
+        let off = on.iter().fold(options, |o, f| f.off(&o));
+        assert!(Filter::iter().all(|f| !f.is_on(&off)));
+    }
+
+    #[test]
+    fn relaxes_until_voicings_are_found() {
+        let options = Options::default();
+        // only finds voicings once a wider stretch is allowed
+        let generate = |o: &Options| {
+            if o.max_span > options.max_span {
+                Voicings::iter(Note::C, Chord::MAJOR, DEFAULT_TUNING, o)
+                    .take(1)
+                    .collect()
+            } else {
+                Vec::new()
+            }
+        };
+        let (voicings, relaxed) = relax_until_found(&options, generate);
+        assert_eq!(voicings.len(), 1);
+        assert_eq!(relaxed, [Relaxation::ThreeStrings, Relaxation::WiderSpan]);
+        let (voicings, relaxed) = relax_until_found(&options, |_| Vec::new());
+        assert!(voicings.is_empty() && relaxed.is_empty());
+    }
+}
@@ -0,0 +1,218 @@
+//! Drawing voicings as chord diagrams.
+
+use crate::{Finger, Hand, Voicing};
+use std::ops::RangeInclusive;
+
+/// Fewest frets a diagram shows.
+const MIN_FRETS: u8 = 4;
+
+/// Distance between neighbouring strings of a drawn diagram.
+const STRING_GAP: f32 = 20.0;
+/// Distance between neighbouring frets of a drawn diagram.
+const FRET_GAP: f32 = 24.0;
+/// Space around the grid, with room for the base fret label on the left.
+const MARGIN: f32 = 30.0;
+/// Height of the title and the open and muted string markers above the grid.
+const HEADER: f32 = 44.0;
+/// Radius of the dots marking fretted notes.
+const DOT: f32 = 8.0;
+
+/// Which part of a text is placed at the point it's drawn at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+/// Something diagrams can be drawn on, in black unless said otherwise. Points are measured
+/// right and down from the top left corner.
+pub(crate) trait Canvas {
+    /// A straight line `width` thick.
+    fn line(&mut self, from: (f32, f32), to: (f32, f32), width: f32);
+    /// A circle of radius `r`, filled or just outlined.
+    fn circle(&mut self, center: (f32, f32), r: f32, filled: bool);
+    /// A line `width` thick from `from` to `to` curving towards `control` on the way.
+    fn curve(&mut self, from: (f32, f32), control: (f32, f32), to: (f32, f32), width: f32);
+    /// `text` in a font `size` high, on a baseline through `at`, in white if `white`.
+    fn text(&mut self, at: (f32, f32), size: f32, anchor: Anchor, white: bool, text: &str);
+}
+
+/// First fret a diagram shows: the nut for voicings that fit below the [`MIN_FRETS`]th fret,
+/// otherwise the lowest fretted one.
+pub fn first_diagram_fret(v: &Voicing) -> u8 {
+    base_fret(&v.frets)
+}
+
+/// The [`first_diagram_fret`] of a voicing fretting `frets`.
+pub(crate) fn base_fret(frets: &[Finger]) -> u8 {
+    let fretted = || frets.iter().filter_map(|f| f.0).filter(|&x| x > 0);
+    match (fretted().min(), fretted().max()) {
+        (Some(lo), Some(hi)) if hi > MIN_FRETS => lo,
+        _ => 1,
+    }
+}
+
+/// The frets a diagram of `v` shows, at least [`MIN_FRETS`] of them.
+pub(crate) fn diagram_frets(v: &Voicing) -> RangeInclusive<u8> {
+    let base = first_diagram_fret(v);
+    let top = v.frets.iter().filter_map(|f| f.0).max().unwrap_or(0);
+    base..=top.max(base + MIN_FRETS - 1)
+}
+
+/// How the finger holding a note is labelled, `T` for the thumb.
+pub(crate) fn finger_label(finger: u8) -> char {
+    match finger {
+        Hand::THUMB => 'T',
+        _ => char::from(b'0' + finger),
+    }
+}
+
+/// The first and last strings, by their index in `v`, of the barre at `fret`. These are the
+/// outermost strings the index finger holds there, whichever way round the strings are listed.
+pub(crate) fn barre_ends(v: &Voicing, fret: u8) -> Option<(usize, usize)> {
+    v.barre.as_ref().filter(|b| b.fret == fret)?;
+    let barred = |i: &usize| {
+        v.frets[*i].0 == Some(fret) && v.fingers.as_ref().is_some_and(|h| h[*i] == Some(1))
+    };
+    let first = (0..v.frets.len()).find(barred)?;
+    let last = (0..v.frets.len()).rfind(barred)?;
+    Some((first, last))
+}
+
+/// A text chord box of `v` with the strings running down from the lowest on the left, like
+///
+/// ```text
+/// x     o
+/// ===========
+/// | | | | 1 |
+/// | | 2 | | |
+/// | 3 | | | |
+/// | | | | | |
+/// ```
+///
+/// Muted strings are marked `x` and open ones `o` above the nut, which is drawn as `-` instead
+/// of `=` when the diagram starts further up the neck, at the fret labelled on its right. Fretted
+/// notes show the finger holding them, `T` for the thumb, and a barre is drawn across its strings
+/// with `-`.
+pub fn ascii_diagram(v: &Voicing) -> String {
+    let frets = diagram_frets(v);
+    let base = *frets.start();
+    let width = v.frets.len() * 2 - 1;
+    let mut lines = Vec::new();
+
+    let markers: Vec<&str> = v
+        .frets
+        .iter()
+        .map(|f| match f.0 {
+            None => "x",
+            Some(0) => "o",
+            Some(_) => " ",
+        })
+        .collect();
+    lines.push(markers.join(" ").trim_end().to_string());
+    lines.push(if base == 1 { "=" } else { "-" }.repeat(width));
+
+    for fret in frets {
+        let mut row: Vec<char> = v
+            .frets
+            .iter()
+            .enumerate()
+            .flat_map(|(i, f)| {
+                let mark = match (f.0, v.fingers.as_ref().and_then(|h| h[i])) {
+                    (Some(x), Some(finger)) if x == fret => finger_label(finger),
+                    (Some(x), None) if x == fret => '*',
+                    _ => '|',
+                };
+                [mark, ' ']
+            })
+            .collect();
+        row.pop();
+        if let Some((first, last)) = barre_ends(v, fret) {
+            for c in &mut row[first * 2..last * 2] {
+                if *c == ' ' || *c == '|' {
+                    *c = '-';
+                }
+            }
+        }
+        let mut line: String = row.into_iter().collect();
+        if fret == base && base > 1 {
+            line.push_str(&format!(" {}fr", base));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+/// The width and height of the diagram [`draw_diagram`] draws of `v`.
+pub(crate) fn diagram_size(v: &Voicing) -> (f32, f32) {
+    let rows = diagram_frets(v).len() as f32;
+    (
+        (v.frets.len() - 1) as f32 * STRING_GAP + 2.0 * MARGIN,
+        HEADER + rows * FRET_GAP + MARGIN / 2.0,
+    )
+}
+
+/// Draw a chord diagram of `v` titled `title` with the strings running down from the lowest on
+/// the left like [`ascii_diagram`]. It shows the nut, or the fret the diagram starts at further
+/// up the neck, muted and open strings, a dot numbered with the finger holding each fretted
+/// note, and an arc over the strings of a barre.
+pub(crate) fn draw_diagram(canvas: &mut impl Canvas, v: &Voicing, title: &str) {
+    let frets = diagram_frets(v);
+    let base = *frets.start();
+    let rows = frets.len() as f32;
+    let (width, _) = diagram_size(v);
+    let x = |i: usize| MARGIN + i as f32 * STRING_GAP;
+    // middle of the space for `fret`, where its notes are fingered
+    let y = |fret: u8| HEADER + (fret - base) as f32 * FRET_GAP + FRET_GAP / 2.0;
+
+    canvas.text((width / 2.0, 16.0), 14.0, Anchor::Middle, false, title);
+
+    // the grid, with a thick nut at the top in first position
+    let bottom = HEADER + rows * FRET_GAP;
+    for i in 0..v.frets.len() {
+        canvas.line((x(i), HEADER), (x(i), bottom), 1.0);
+    }
+    for row in 0..=frets.len() {
+        let nut = row == 0 && base == 1;
+        let top = HEADER + row as f32 * FRET_GAP;
+        let thickness = if nut { 4.0 } else { 1.0 };
+        canvas.line((MARGIN, top), (width - MARGIN, top), thickness);
+    }
+    if base > 1 {
+        let label = format!("{}fr", base);
+        canvas.text(
+            (MARGIN - DOT, y(base) + 4.0),
+            11.0,
+            Anchor::End,
+            false,
+            &label,
+        );
+    }
+
+    for (i, f) in v.frets.iter().enumerate() {
+        let marker = HEADER - 10.0;
+        match f.0 {
+            None => {
+                let d = 3.5;
+                canvas.line((x(i) - d, marker - d), (x(i) + d, marker + d), 1.5);
+                canvas.line((x(i) - d, marker + d), (x(i) + d, marker - d), 1.5);
+            }
+            Some(0) => canvas.circle((x(i), marker), 4.5, false),
+            Some(fret) => {
+                canvas.circle((x(i), y(fret)), DOT, true);
+                if let Some(finger) = v.fingers.as_ref().and_then(|h| h[i]) {
+                    let label = finger_label(finger).to_string();
+                    canvas.text((x(i), y(fret) + 4.0), 11.0, Anchor::Middle, true, &label);
+                }
+            }
+        }
+    }
+
+    if let Some(barre) = &v.barre {
+        if let Some((first, last)) = barre_ends(v, barre.fret) {
+            let (x1, x2, top) = (x(first), x(last), y(barre.fret) - DOT - 2.0);
+            canvas.curve((x1, top), ((x1 + x2) / 2.0, top - 10.0), (x2, top), 2.0);
+        }
+    }
+}
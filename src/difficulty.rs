@@ -0,0 +1,65 @@
+//! Grading voicings by how hard they are to play.
+
+use crate::{first_position_frets, reach, Finger, Hand, ParseError};
+use serde::Serialize;
+use std::{fmt, str::FromStr};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// How much practice a voicing takes, easiest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, EnumIter)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    /// Up to three fingers in first position, without barres or stretches.
+    Beginner,
+    /// A barre, all four fingers, a stretch across four frets or a position up the neck.
+    Intermediate,
+    /// Wider stretches or the thumb over the neck, or more fingers than a hand has.
+    Advanced,
+}
+
+impl Difficulty {
+    /// Grade `fingering` held with `hand`, on a neck of `scale` length.
+    pub fn of(fingering: &[Finger], hand: Option<&Hand>, scale: f32) -> Difficulty {
+        let Some(hand) = hand else {
+            return Difficulty::Advanced;
+        };
+        // a little slack for rounding errors
+        let span = first_position_frets(reach(fingering, scale), scale) - 0.01;
+        let thumb = hand.fingers.contains(&Some(Hand::THUMB));
+        let highest = fingering.iter().filter_map(|f| f.0).max().unwrap_or(0);
+        if thumb || span > 4.0 {
+            Difficulty::Advanced
+        } else if hand.barre.is_some() || hand.count() > 3 || span > 3.0 || highest > 5 {
+            Difficulty::Intermediate
+        } else {
+            Difficulty::Beginner
+        }
+    }
+
+    /// Lowercase name, e.g. `"beginner"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Intermediate => "intermediate",
+            Difficulty::Advanced => "advanced",
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = ParseError;
+
+    /// Parse a [`Difficulty::name`], case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Difficulty::iter()
+            .find(|d| d.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseError::UnknownDifficulty(s.to_string()))
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -0,0 +1,61 @@
+//! Picking voicings that differ from each other, rather than the same shape a fret apart.
+
+use crate::{Finger, Voicing};
+
+/// Voicings closer than this by [`shape_distance`] are near enough the same shape.
+pub const MIN_DISTANCE: u32 = 3;
+
+/// How different two fingerings of the same strings are: the frets each string moves by, open
+/// strings counting as fret 0, and 2 for each string played in one and muted in the other.
+pub fn shape_distance(a: &[Finger], b: &[Finger]) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| match (a.0, b.0) {
+            (Some(a), Some(b)) => u32::from(a.abs_diff(b)),
+            (None, None) => 0,
+            _ => 2,
+        })
+        .sum()
+}
+
+/// `n` of `voicings`, which are best first, that differ from each other. Going down the list,
+/// each voicing at least [`MIN_DISTANCE`] from the ones picked so far is picked, and when that
+/// leaves fewer than `n` the rest are picked greedily, each the furthest from its nearest picked
+/// voicing. They stay in the order they came in.
+pub fn diverse(voicings: Vec<Voicing>, n: usize) -> Vec<Voicing> {
+    if voicings.len() <= n {
+        return voicings;
+    }
+    let nearest = |i: usize, picked: &[usize]| {
+        picked
+            .iter()
+            .map(|&j| shape_distance(&voicings[i].frets, &voicings[j].frets))
+            .min()
+            .unwrap_or(u32::MAX)
+    };
+    let mut picked: Vec<usize> = Vec::with_capacity(n);
+    for i in 0..voicings.len() {
+        if picked.len() == n {
+            break;
+        }
+        if nearest(i, &picked) >= MIN_DISTANCE {
+            picked.push(i);
+        }
+    }
+    while picked.len() < n {
+        // ties go to the better voicing
+        let Some(next) = (0..voicings.len())
+            .filter(|i| !picked.contains(i))
+            .max_by_key(|&i| (nearest(i, &picked), std::cmp::Reverse(i)))
+        else {
+            break;
+        };
+        picked.push(next);
+    }
+    picked.sort_unstable();
+    let mut voicings: Vec<Option<Voicing>> = voicings.into_iter().map(Some).collect();
+    picked
+        .into_iter()
+        .filter_map(|i| voicings[i].take())
+        .collect()
+}
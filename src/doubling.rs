@@ -0,0 +1,62 @@
+//! Counting how often each chord tone sounds in a voicing.
+
+use crate::{Chord, Note, Pitch};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// How many strings sound each chord tone of a voicing, by scale degree. Notes outside the chord,
+/// like a slash chord's bass, aren't counted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Doubling(pub BTreeMap<u8, u8>);
+
+impl Doubling {
+    /// The doubling of a voicing of `chord` built on `root` sounding `pitches`.
+    pub fn of(root: Note, chord: Chord, pitches: &[Option<Pitch>]) -> Doubling {
+        let tones = chord.tones();
+        let mut counts = BTreeMap::new();
+        for p in pitches.iter().flatten() {
+            if let Some(t) = tones.iter().find(|t| root + t.semitones == p.note()) {
+                *counts.entry(t.degree).or_insert(0) += 1;
+            }
+        }
+        Doubling(counts)
+    }
+
+    /// How many strings sound the tone of this degree.
+    pub fn count(&self, degree: u8) -> u8 {
+        self.0.get(&degree).copied().unwrap_or(0)
+    }
+
+    /// Does the voicing follow the strict doubling rules: the root at most doubled, and the
+    /// leading third of a dominant chord not doubled at all.
+    pub fn is_strict(&self, chord: Chord) -> bool {
+        let tones = chord.tones();
+        let has = |degree, semitones| {
+            tones
+                .iter()
+                .any(|t| t.degree == degree && t.semitones == semitones)
+        };
+        let dominant = has(3, 4) && has(7, 10);
+        self.count(1) <= 2 && !(dominant && self.count(3) > 1)
+    }
+
+    /// Score penalty of `weight` points for each doubling of tones other than the root and
+    /// fifth, which are the ones that can be doubled without unbalancing the chord.
+    pub fn penalty(&self, weight: u32) -> u32 {
+        self.0
+            .iter()
+            .filter(|(&degree, _)| degree != 1 && degree != 5)
+            .map(|(_, &count)| weight * count.saturating_sub(1) as u32)
+            .sum()
+    }
+}
+
+/// How many pairs of neighbouring strings in `pitches` sound the very same pitch, so one of them
+/// adds nothing. The same note an octave apart doesn't count.
+pub fn adjacent_unisons(pitches: &[Option<Pitch>]) -> usize {
+    pitches
+        .windows(2)
+        .filter(|pair| pair[0].is_some() && pair[0] == pair[1])
+        .count()
+}
@@ -0,0 +1,136 @@
+//! Close and drop voicings of four-note chords.
+
+use crate::{ParseError, Pitch};
+use serde::Serialize;
+use std::{fmt, str::FromStr};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// How the four notes of a voicing are spread out, compared to stacking them as closely as
+/// possible within an octave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, EnumIter)]
+pub enum DropVoicing {
+    /// All four notes within an octave, each next to the following chord tone.
+    #[serde(rename = "close")]
+    Close,
+    /// A close voicing with the second note from the top dropped an octave.
+    #[serde(rename = "drop-2")]
+    Drop2,
+    /// A close voicing with the third note from the top dropped an octave.
+    #[serde(rename = "drop-3")]
+    Drop3,
+    /// A close voicing with the second and fourth notes from the top dropped an octave.
+    #[serde(rename = "drop-2&4")]
+    Drop24,
+}
+
+/// Are the pitches, lowest first, a close voicing: rising and within an octave.
+fn is_close(pitches: [u8; 4]) -> bool {
+    pitches.windows(2).all(|w| w[0] < w[1]) && pitches[3] - pitches[0] < 12
+}
+
+impl DropVoicing {
+    /// Classify a voicing sounding `pitches`. Only voicings of four strings sounding four
+    /// different notes have a drop voicing, `None` for the others and for wider spreads.
+    pub fn of(pitches: &[Option<Pitch>]) -> Option<DropVoicing> {
+        let mut sounding: Vec<u8> = pitches.iter().flatten().map(|p| p.0).collect();
+        sounding.sort_unstable();
+        let [a, b, c, d] = <[u8; 4]>::try_from(sounding).ok()?;
+        let mut classes = [a % 12, b % 12, c % 12, d % 12];
+        classes.sort_unstable();
+        if classes.windows(2).any(|w| w[0] == w[1]) {
+            return None;
+        }
+        // raise the dropped notes back up an octave and see if that gives a close voicing
+        if is_close([a, b, c, d]) {
+            Some(DropVoicing::Close)
+        } else if is_close([b, c, a + 12, d]) {
+            Some(DropVoicing::Drop2)
+        } else if is_close([b, a + 12, c, d]) {
+            Some(DropVoicing::Drop3)
+        } else if is_close([a + 12, c, b + 12, d]) {
+            Some(DropVoicing::Drop24)
+        } else {
+            None
+        }
+    }
+
+    /// Name used in the output, e.g. `"drop-2"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DropVoicing::Close => "close",
+            DropVoicing::Drop2 => "drop-2",
+            DropVoicing::Drop3 => "drop-3",
+            DropVoicing::Drop24 => "drop-2&4",
+        }
+    }
+}
+
+impl FromStr for DropVoicing {
+    type Err = ParseError;
+
+    /// Parse a [`DropVoicing::name`], also accepting `"drop2"` and `"drop-2-4"` like spellings.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let simplified = |s: &str| {
+            s.to_ascii_lowercase()
+                .replace(['-', '&', ' '], "")
+                .replace("and", "")
+        };
+        DropVoicing::iter()
+            .find(|d| simplified(d.name()) == simplified(s))
+            .ok_or_else(|| ParseError::UnknownDropVoicing(s.to_string()))
+    }
+}
+
+impl fmt::Display for DropVoicing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn of(midi: &[u8]) -> Option<DropVoicing> {
+        let pitches: Vec<Option<Pitch>> = midi.iter().map(|&p| Some(Pitch(p))).collect();
+        DropVoicing::of(&pitches)
+    }
+
+    #[test]
+    fn classifies_cmaj7_voicings() {
+        // C4 E4 G4 B4 and the same notes with some dropped an octave
+        assert_eq!(of(&[60, 64, 67, 71]), Some(DropVoicing::Close));
+        assert_eq!(of(&[55, 60, 64, 71]), Some(DropVoicing::Drop2));
+        assert_eq!(of(&[52, 60, 67, 71]), Some(DropVoicing::Drop3));
+        assert_eq!(of(&[48, 55, 64, 71]), Some(DropVoicing::Drop24));
+    }
+
+    #[test]
+    fn only_four_different_notes_have_one() {
+        assert_eq!(of(&[48, 52, 55, 60]), None);
+        assert_eq!(of(&[48, 52, 55, 59, 62]), None);
+        assert_eq!(of(&[36, 64, 67, 71]), None);
+        let muted = [
+            None,
+            Some(Pitch(60)),
+            Some(Pitch(64)),
+            Some(Pitch(67)),
+            Some(Pitch(71)),
+        ];
+        assert_eq!(DropVoicing::of(&muted), Some(DropVoicing::Close));
+    }
+
+    #[test]
+    fn parses_names() {
+        assert_eq!("drop2".parse::<DropVoicing>().unwrap(), DropVoicing::Drop2);
+        assert_eq!(
+            "Drop-2-4".parse::<DropVoicing>().unwrap(),
+            DropVoicing::Drop24
+        );
+        assert_eq!(
+            "drop-5".parse::<DropVoicing>().unwrap_err().to_string(),
+            "unknown drop voicing 'drop-5'"
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Writing voicings as Guitar Pro 5 files, to open them in Guitar Pro or TuxGuitar.
+
+use crate::{ChordSymbol, Pitch, Voicing};
+
+/// The most strings a Guitar Pro 5 track can have.
+pub const GP5_STRINGS: usize = 7;
+const VERSION: &str = "FICHIER GUITAR PRO v5.10";
+const TEMPO: i32 = 120;
+/// General MIDI's steel-string acoustic guitar, numbered from 0.
+const GUITAR: i32 = 25;
+/// A whole note, in Guitar Pro's durations counted from -2 up to a 64th at 4.
+const WHOLE: u8 = (-2i8) as u8;
+/// Track settings: showing the tablature and the standard notation, with the chord diagrams
+/// listed at the top of the score and drawn over the beats.
+const TRACK_SETTINGS: i16 = 0x0001 | 0x0002 | 0x0040 | 0x0080;
+
+/// A Guitar Pro 5 file titled `title` with a track for `instrument` tuned to `tuning`, from the
+/// lowest string, with a capo at `capo`. Each of `chords` gets a bar of 4/4, a whole note
+/// strummed under its chord diagram. The strings are numbered from the highest as Guitar Pro
+/// does, so the voicings mustn't be mirrored for left-handed players, and there can be at most
+/// [`GP5_STRINGS`] of them.
+pub fn gp5_file(
+    title: &str,
+    instrument: &str,
+    tuning: &[Pitch],
+    capo: u8,
+    chords: &[(ChordSymbol, Voicing)],
+) -> Vec<u8> {
+    let mut out = Gp5(Vec::new());
+    out.byte_size_string(VERSION, 30);
+    // title, subtitle, artist, album, words, music, copyright, tab and instructions
+    out.int_byte_size_string(title);
+    for _ in 0..8 {
+        out.int_byte_size_string("");
+    }
+    // notice lines
+    out.int(0);
+    // lyrics for no track, five empty lines starting at the first bar
+    out.int(0);
+    for _ in 0..5 {
+        out.int(1);
+        out.int(0);
+    }
+    // master volume and the master equalizer's ten bands and gain
+    out.int(100);
+    out.int(0);
+    out.0.extend([0; 11]);
+    write_page_setup(&mut out);
+    out.int_byte_size_string("Moderate");
+    out.int(TEMPO);
+    // the tempo is shown
+    out.byte(0);
+    // C major, and its octave
+    out.byte(0);
+    out.int(0);
+    write_midi_channels(&mut out);
+    // no coda, segno or other directions
+    for _ in 0..19 {
+        out.short(-1);
+    }
+    // master reverb
+    out.int(0);
+
+    let measures = chords.len().max(1);
+    out.int(measures as i32);
+    out.int(1);
+    for i in 0..measures {
+        if i == 0 {
+            // 4/4, beamed in eighths
+            out.0.extend([0x03, 4, 4, 2, 2, 2, 2]);
+        } else {
+            out.0.extend([0x00, 0x00]);
+        }
+        // no alternate ending, and straight eighths
+        out.0.extend([0x00, 0x00]);
+    }
+    write_track(&mut out, instrument, tuning, capo);
+    out.byte(0);
+    for (symbol, v) in chords {
+        write_measure(&mut out, Some((*symbol, v)));
+    }
+    // a score needs a bar even without any chords
+    if chords.is_empty() {
+        write_measure(&mut out, None);
+    }
+    out.0
+}
+
+/// A Guitar Pro 5 file being written, with the little-endian numbers and the kinds of strings it
+/// has.
+struct Gp5(Vec<u8>);
+
+impl Gp5 {
+    fn byte(&mut self, b: u8) {
+        self.0.push(b);
+    }
+
+    fn short(&mut self, n: i16) {
+        self.0.extend(n.to_le_bytes());
+    }
+
+    fn int(&mut self, n: i32) {
+        self.0.extend(n.to_le_bytes());
+    }
+
+    /// `s` in Latin-1, the characters it doesn't have as `?`.
+    fn latin1(s: &str) -> Vec<u8> {
+        s.chars().map(|c| u8::try_from(c).unwrap_or(b'?')).collect()
+    }
+
+    /// `s` after its length in a byte, padded with zeroes to `size` bytes.
+    fn byte_size_string(&mut self, s: &str, size: usize) {
+        let mut bytes = Gp5::latin1(s);
+        bytes.truncate(size);
+        self.byte(bytes.len() as u8);
+        bytes.resize(size, 0);
+        self.0.extend(bytes);
+    }
+
+    /// `s` after its length in a byte, after the size of both in an int.
+    fn int_byte_size_string(&mut self, s: &str) {
+        let mut bytes = Gp5::latin1(s);
+        bytes.truncate(u8::MAX as usize);
+        self.int(bytes.len() as i32 + 1);
+        self.byte(bytes.len() as u8);
+        self.0.extend(bytes);
+    }
+}
+
+/// An A4 page with the headers and footers Guitar Pro puts on new scores.
+fn write_page_setup(out: &mut Gp5) {
+    // size and margins in millimetres, and the score at full size
+    for n in [210, 297, 10, 10, 15, 10, 100] {
+        out.int(n);
+    }
+    out.short(0x01FF);
+    for s in [
+        "%title%",
+        "%subtitle%",
+        "%artist%",
+        "%album%",
+        "Words by %words%",
+        "Music by %music%",
+        "Words & Music by %WORDSMUSIC%",
+        "Copyright %copyright%",
+        "All Rights Reserved - International Copyright Secured",
+        "Page %N%/%P%",
+    ] {
+        out.int_byte_size_string(s);
+    }
+}
+
+/// The 16 channels of each of the 4 MIDI ports, all playing the guitar but for the drums.
+fn write_midi_channels(out: &mut Gp5) {
+    for channel in 0..64 {
+        out.int(if channel % 16 == 9 { 0 } else { GUITAR });
+        // volume and balance in sixteenths, then no chorus, reverb, phaser or tremolo
+        out.0.extend([13, 8, 0, 0, 0, 0]);
+        out.0.extend([0, 0]);
+    }
+}
+
+fn write_track(out: &mut Gp5, instrument: &str, tuning: &[Pitch], capo: u8) {
+    out.byte(0);
+    // shown
+    out.byte(0x08);
+    out.byte_size_string(instrument, 40);
+    out.int(tuning.len() as i32);
+    for i in 0..GP5_STRINGS {
+        // from the highest string
+        let pitch = tuning.len().checked_sub(i + 1).map_or(0, |s| tuning[s].0);
+        out.int(i32::from(pitch));
+    }
+    // the first port, on the first channel with its effects on the second
+    out.int(1);
+    out.int(1);
+    out.int(2);
+    out.int(24);
+    out.int(i32::from(capo));
+    // red
+    out.0.extend([255, 0, 0, 0]);
+    out.short(TRACK_SETTINGS);
+    // no auto accentuation, the first bank
+    out.0.extend([0, 0]);
+    // RSE, the Realistic Sound Engine, left off
+    out.byte(0);
+    out.int(0);
+    out.int(0);
+    out.int(100);
+    out.0.extend([0; 12]);
+    for _ in 0..4 {
+        out.int(-1);
+    }
+    out.0.extend([0; 4]);
+    out.int_byte_size_string("");
+    out.int_byte_size_string("");
+}
+
+/// A bar with a whole note of `chord`'s voicing under its diagram, or a whole rest without one.
+fn write_measure(out: &mut Gp5, chord: Option<(ChordSymbol, &Voicing)>) {
+    // the first voice, the second is empty
+    out.int(1);
+    match chord {
+        Some((symbol, v)) => {
+            out.byte(0x02);
+            out.byte(WHOLE);
+            write_chord(out, symbol, v);
+            write_notes(out, v);
+        }
+        None => {
+            // a rest
+            out.0.extend([0x40, 0x02]);
+            out.byte(WHOLE);
+            out.byte(0);
+        }
+    }
+    out.short(0);
+    out.int(0);
+    // no line break
+    out.byte(0);
+}
+
+/// The diagram of `symbol` played as `v`, named after it. The fields spelling out the chord's
+/// root, type and alterations are left empty, the name is what's shown.
+fn write_chord(out: &mut Gp5, symbol: ChordSymbol, v: &Voicing) {
+    // the chord format of Guitar Pro 4 and up
+    out.byte(1);
+    out.0.extend([0; 16]);
+    out.byte_size_string(&symbol.to_string(), 21);
+    out.0.extend([0; 4]);
+    out.int(i32::from(v.base_fret));
+    for i in 0..GP5_STRINGS {
+        let fret = v.frets.len().checked_sub(i + 1).and_then(|s| v.frets[s].0);
+        out.int(fret.map_or(-1, i32::from));
+    }
+    // no barres or omitted tones
+    out.0.extend([0; 16]);
+    out.0.extend([0; 7]);
+    out.byte(0);
+    for i in 0..GP5_STRINGS {
+        let finger = v.frets.len().checked_sub(i + 1).and_then(|s| finger(v, s));
+        out.byte(finger.map_or(-1, |f| f as i8) as u8);
+    }
+    // shown
+    out.byte(1);
+}
+
+/// The fretted and open notes of `v`, each with the finger holding it.
+fn write_notes(out: &mut Gp5, v: &Voicing) {
+    let strings = v.frets.len();
+    // a bit for each string from the highest, down from the second most significant
+    let mut played = 0u8;
+    for (s, f) in v.frets.iter().enumerate() {
+        if f.0.is_some() {
+            played |= 1 << (7 - (strings - s));
+        }
+    }
+    out.byte(played);
+    for s in (0..strings).rev() {
+        let Some(fret) = v.frets[s].0 else {
+            continue;
+        };
+        match finger(v, s) {
+            Some(finger) => {
+                out.0.extend([0x20 | 0x80, 1, fret, finger, (-1i8) as u8]);
+            }
+            None => out.0.extend([0x20, 1, fret]),
+        }
+        // accidentals spelled as usual
+        out.byte(0);
+    }
+}
+
+/// Finger holding string `s` of `v`, if it's fretted and can be held.
+fn finger(v: &Voicing, s: usize) -> Option<u8> {
+    v.fingers.as_ref().and_then(|fingers| fingers[s])
+}
@@ -0,0 +1,106 @@
+//! Working out which fretting-hand finger holds each note.
+
+use crate::{Barre, Finger};
+use itertools::Itertools;
+use serde::Serialize;
+
+/// The fretting-hand fingers holding a fingering.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Hand {
+    /// Finger fretting each string, 1 for the index finger to 4 for the little finger or
+    /// [`Hand::THUMB`], `None` for open and muted strings.
+    pub fingers: Vec<Option<u8>>,
+    /// The barre held with the index finger, if it's used for one.
+    pub barre: Option<Barre>,
+}
+
+/// Give each of the `notes`, `(string, fret)` pairs sorted by fret, its own finger from `first`
+/// up to the little finger, in the same order across the neck. Returns the assignment straying
+/// least from one finger per fret above `base`, and by how many frets it strays.
+fn assign(notes: &[(usize, u8)], first: u8, base: u8) -> Option<(u32, Vec<(usize, u8)>)> {
+    (first..=4)
+        .combinations(notes.len())
+        .map(|fingers| {
+            let cost = notes
+                .iter()
+                .zip(&fingers)
+                .map(|(&(_, fret), &finger)| (finger - 1).abs_diff(fret - base) as u32)
+                .sum();
+            (cost, notes.iter().map(|n| n.0).zip(fingers).collect())
+        })
+        .min_by_key(|(cost, _)| *cost)
+}
+
+/// Fingers `(string, finger)` holding `fingering` without the thumb, the barre if one is used,
+/// and the cost of holding it that way.
+type Grip = (u32, Vec<(usize, u8)>, Option<Barre>);
+
+/// Hold `fingering` with or without an index finger [`Barre`], whichever keeps closer to one
+/// finger per fret.
+fn grip(fingering: &[Finger]) -> Option<Grip> {
+    let mut fretted: Vec<(usize, u8)> = fingering
+        .iter()
+        .enumerate()
+        .filter_map(|(i, f)| f.0.filter(|&x| x > 0).map(|x| (i, x)))
+        .collect();
+    // lower strings take lower fingers on the same fret
+    fretted.sort_by_key(|&(i, x)| (x, i));
+    let Some(&(_, base)) = fretted.first() else {
+        return Some((0, Vec::new(), None));
+    };
+    let plain = assign(&fretted, 1, base).map(|(cost, notes)| (cost, notes, None));
+    let barred = Barre::of(fingering).and_then(|barre| {
+        let (under, rest): (Vec<_>, Vec<_>) = fretted.iter().partition(|&&(i, x)| {
+            x == barre.fret && barre.strings.contains(&((fingering.len() - i) as u8))
+        });
+        let (cost, mut notes) = assign(&rest, 2, base)?;
+        notes.extend(under.iter().map(|&(i, _)| (i, 1)));
+        // only barre when it's clearly easier than fretting each note
+        Some((cost + 3, notes, Some(barre)))
+    });
+    plain
+        .into_iter()
+        .chain(barred)
+        .min_by_key(|(cost, ..)| *cost)
+}
+
+/// Hold the lowest string of `fingering` with the thumb wrapped over the neck, and the rest with
+/// the fingers. The thumb can't reach past the index finger.
+fn thumb_grip(fingering: &[Finger]) -> Option<Grip> {
+    let bass = fingering.first()?.0.filter(|&x| x > 0)?;
+    let mut rest = fingering.to_vec();
+    rest[0] = Finger(None);
+    if rest.iter().filter_map(|f| f.0).any(|x| x > 0 && x < bass) {
+        return None;
+    }
+    let (cost, mut notes, barre) = grip(&rest)?;
+    notes.push((0, Hand::THUMB));
+    // not everyone plays with the thumb, so prefer the fingers
+    Some((cost + 2, notes, barre))
+}
+
+impl Hand {
+    /// Finger number of the thumb.
+    pub const THUMB: u8 = 0;
+
+    /// Assign fingers to `fingering`, letting the thumb fret the lowest string when `thumb` is
+    /// set, like the F# of a Hendrix style D/F#. `None` if it needs more than four fingers and
+    /// the thumb.
+    pub fn of(fingering: &[Finger], thumb: bool) -> Option<Hand> {
+        let thumbed = if thumb { thumb_grip(fingering) } else { None };
+        let (_, notes, barre) = grip(fingering)
+            .into_iter()
+            .chain(thumbed)
+            .min_by_key(|(cost, ..)| *cost)?;
+        let mut fingers = vec![None; fingering.len()];
+        for (i, finger) in notes {
+            fingers[i] = Some(finger);
+        }
+        Some(Hand { fingers, barre })
+    }
+
+    /// How many different fingers are used, counting the thumb.
+    pub fn count(&self) -> usize {
+        self.fingers.iter().flatten().unique().count()
+    }
+}
@@ -0,0 +1,199 @@
+//! Naming the chord a set of notes makes.
+
+use crate::{Chord, ChordSymbol, Note, NoteSet};
+use std::cmp::Reverse;
+use strum::IntoEnumIterator;
+
+/// The chords out of `chords` that sound exactly `notes` with `bass` the lowest, best first.
+/// Every tone of a chord has to be there but the ones it may leave out, see [`Chord::tones`],
+/// and no other notes except a bass below it. A bass other than the root makes a slash chord,
+/// like C/E.
+///
+/// Chords with the root in the bass come first, then those with the bass in the chord, then
+/// slash chords, each by how few tones are left out and then in the order of `chords`.
+pub fn identify(notes: NoteSet, bass: Note, chords: &[Chord]) -> Vec<ChordSymbol> {
+    let mut found = Vec::new();
+    for root in Note::iter().filter(|&r| notes.contains(r)) {
+        for (order, &chord) in chords.iter().enumerate() {
+            let tones: NoteSet = chord.notes(root).into_iter().collect();
+            let optional: NoteSet = chord.optional_notes(root).into_iter().collect();
+            // a bass outside the chord is the slash chord's own note
+            let sounding = if tones.contains(bass) {
+                notes
+            } else {
+                notes & !NoteSet::of(bass)
+            };
+            if sounding & !tones != NoteSet::default()
+                || (tones & !optional) & !sounding != NoteSet::default()
+            {
+                continue;
+            }
+            let slash = if bass == root {
+                0
+            } else if tones.contains(bass) {
+                1
+            } else {
+                2
+            };
+            let missing = (tones & !sounding).len();
+            let symbol = ChordSymbol {
+                root,
+                chord,
+                bass: (bass != root).then_some(bass),
+            };
+            found.push(((slash, missing, order), symbol));
+        }
+    }
+    found.sort_by_key(|(rank, _)| *rank);
+    found.into_iter().map(|(_, symbol)| symbol).collect()
+}
+
+/// A chord that a set of notes could be played as, found by [`identify_fuzzy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub symbol: ChordSymbol,
+    /// How likely the notes are this chord, from 100 for exactly its notes over the root down.
+    pub confidence: u8,
+    /// Tones of the chord that aren't played, in the order of [`Chord::tones`].
+    pub missing: Vec<Note>,
+    /// Notes played that aren't in the chord, like added tensions, from C up.
+    pub extra: Vec<Note>,
+}
+
+/// Candidates below this confidence aren't worth naming.
+const MIN_CONFIDENCE: u8 = 50;
+
+/// The chords out of `chords` that `notes` could be with `bass` the lowest, allowing for the
+/// tones real voicings leave out and the ones they add, most likely first. Each one left out
+/// or added makes it less likely: a tone [`Chord::tones`] says may be left out, like the
+/// fifth, hardly at all, the root more, as a bass player usually has it, and the third or the
+/// seventh most. Notes outside the chord count as added tensions, except a bass under the
+/// chord which makes it a slash chord. Rootless chords are named without a bass note.
+pub fn identify_fuzzy(notes: NoteSet, bass: Note, chords: &[Chord]) -> Vec<Candidate> {
+    let mut found = Vec::new();
+    for root in Note::iter() {
+        for (order, &chord) in chords.iter().enumerate() {
+            let tones: NoteSet = chord.notes(root).into_iter().collect();
+            let optional: NoteSet = chord.optional_notes(root).into_iter().collect();
+            if (notes & tones).len() < 2 {
+                continue;
+            }
+            let rootless = !notes.contains(root);
+            let slash = !rootless && !tones.contains(bass);
+            let missing: Vec<Note> = chord
+                .notes(root)
+                .into_iter()
+                .filter(|&n| !notes.contains(n))
+                .collect();
+            let extra: Vec<Note> = Note::iter()
+                .filter(|&n| notes.contains(n) && !tones.contains(n) && !(slash && n == bass))
+                .collect();
+            let mut penalty = 15 * extra.len();
+            for &n in &missing {
+                penalty += if n == root {
+                    20
+                } else if optional.contains(n) {
+                    5
+                } else {
+                    25
+                };
+            }
+            if slash {
+                penalty += 10;
+            } else if !rootless && bass != root {
+                penalty += 3;
+            }
+            let Some(confidence) = 100usize.checked_sub(penalty) else {
+                continue;
+            };
+            if confidence < usize::from(MIN_CONFIDENCE) {
+                continue;
+            }
+            let symbol = ChordSymbol {
+                root,
+                chord,
+                bass: (!rootless && bass != root).then_some(bass),
+            };
+            let candidate = Candidate {
+                symbol,
+                confidence: confidence as u8,
+                missing,
+                extra,
+            };
+            found.push((order, candidate));
+        }
+    }
+    found.sort_by_key(|(order, c)| {
+        (
+            Reverse(c.confidence),
+            c.missing.len() + c.extra.len(),
+            *order,
+        )
+    });
+    found.into_iter().map(|(_, c)| c).collect()
+}
+
+/// The chords out of `chords` with all of `notes` among their tones, like the chords a melody
+/// fits or ones to let open strings ring through, by root and then in the order of `chords`.
+pub fn containing(notes: NoteSet, chords: &[Chord]) -> Vec<ChordSymbol> {
+    Note::iter()
+        .flat_map(|root| chords.iter().map(move |&chord| (root, chord)))
+        .filter(|&(root, chord)| {
+            let tones: NoteSet = chord.notes(root).into_iter().collect();
+            notes & !tones == NoteSet::default()
+        })
+        .map(|(root, chord)| ChordSymbol {
+            root,
+            chord,
+            bass: None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Note::*;
+
+    fn chords() -> Vec<Chord> {
+        Chord::builtins().collect()
+    }
+
+    fn notes(notes: &[Note]) -> NoteSet {
+        notes.iter().copied().collect()
+    }
+
+    #[test]
+    fn names_chords_by_their_notes_and_bass() {
+        let found = identify(notes(&[C, E, G]), C, &chords());
+        assert_eq!(found[0].to_string(), "C");
+        let found = identify(notes(&[C, E, G]), E, &chords());
+        assert_eq!(found[0].to_string(), "C/E");
+        let found = identify(notes(&[G, B, D, F]), G, &chords());
+        assert_eq!(found[0].to_string(), "G7");
+        assert!(identify(notes(&[C, CSharp, D]), C, &chords()).is_empty());
+    }
+
+    #[test]
+    fn fuzzy_names_chords_missing_or_adding_a_tone() {
+        // a C9 without its root
+        let found = identify_fuzzy(notes(&[E, G, ASharp, D]), E, &chords());
+        let c9 = found.iter().find(|c| c.symbol.to_string() == "C9").unwrap();
+        assert_eq!(c9.missing, [C]);
+        assert!(c9.extra.is_empty());
+        assert!(c9.confidence < 100);
+        let exact = identify_fuzzy(notes(&[C, E, G]), C, &chords());
+        assert_eq!(exact[0].symbol.to_string(), "C");
+        assert_eq!(exact[0].confidence, 100);
+    }
+
+    #[test]
+    fn lists_chords_containing_notes() {
+        let found = containing(notes(&[C, E, G]), &chords());
+        let names: Vec<String> = found.iter().map(|s| s.to_string()).collect();
+        assert!(names.contains(&"C".to_string()));
+        assert!(names.contains(&"Am7".to_string()));
+        assert!(!names.contains(&"G".to_string()));
+        assert!(found.iter().all(|s| s.bass.is_none()));
+    }
+}
@@ -0,0 +1,164 @@
+//! Fretted instruments with their default tuning and generation limits.
+
+use crate::{parse_tuning, Options, Tuning};
+
+/// A fretted instrument, describing what a typical player can reach on it.
+#[derive(Debug, Clone, Copy)]
+pub struct Instrument {
+    /// Identifier used on the command line, e.g. `"ukulele"`.
+    pub name: &'static str,
+    /// Tuning used when none is given explicitly, as accepted by [`parse_tuning`].
+    pub tuning: &'static str,
+    /// Highest fret worth generating fingerings for.
+    pub max_fret: u8,
+    /// Fret each string begins at, see [`Options::string_starts`].
+    pub string_starts: &'static [u8],
+    /// Fewest strings a fingering should sound on this instrument.
+    pub min_strings: usize,
+    /// Most strings a fingering should sound on this instrument, if limited.
+    pub max_strings: Option<usize>,
+    /// Fewest different chord notes a fingering should sound, if not all of them.
+    pub min_chord_notes: Option<usize>,
+    /// Whether fingerings have to sound the root lowest.
+    pub root_in_bass: bool,
+    /// Strings added below the range of a six string guitar.
+    pub extra_low_strings: usize,
+    /// Distance from the nut to the bridge, in millimetres.
+    pub scale_length: f32,
+}
+
+impl Instrument {
+    /// The open-string pitches of the default tuning.
+    pub fn pitches(&self) -> Tuning {
+        parse_tuning(self.tuning).unwrap()
+    }
+
+    /// Generation options with this instrument's limits and all filters enabled.
+    pub fn options(&self) -> Options {
+        Options {
+            max_fret: self.max_fret,
+            string_starts: self.string_starts.to_vec(),
+            min_strings: self.min_strings,
+            max_strings: self.max_strings,
+            min_chord_notes: self.min_chord_notes,
+            root_in_bass: self.root_in_bass,
+            extra_low_strings: self.extra_low_strings,
+            scale_length: self.scale_length,
+            ..Options::default()
+        }
+    }
+}
+
+/// All the built-in instruments, guitar first.
+pub const INSTRUMENTS: &[Instrument] = &[
+    Instrument {
+        name: "guitar",
+        tuning: "E2 A2 D3 G3 B3 E4",
+        max_fret: crate::MAX_FRETS,
+        string_starts: &[],
+        min_strings: 4,
+        max_strings: None,
+        min_chord_notes: None,
+        root_in_bass: false,
+        extra_low_strings: 0,
+        scale_length: 648.0,
+    },
+    Instrument {
+        name: "guitar-7",
+        tuning: "B1 E2 A2 D3 G3 B3 E4",
+        max_fret: crate::MAX_FRETS,
+        string_starts: &[],
+        min_strings: 4,
+        max_strings: None,
+        min_chord_notes: None,
+        root_in_bass: false,
+        extra_low_strings: 1,
+        scale_length: 648.0,
+    },
+    Instrument {
+        name: "guitar-8",
+        tuning: "F#1 B1 E2 A2 D3 G3 B3 E4",
+        max_fret: crate::MAX_FRETS,
+        string_starts: &[],
+        min_strings: 4,
+        max_strings: None,
+        min_chord_notes: None,
+        root_in_bass: false,
+        extra_low_strings: 2,
+        scale_length: 686.0,
+    },
+    // Re-entrant GCEA tuning, the G string is higher than the C string next to it. With only
+    // four strings, three note chords have to be allowed too.
+    Instrument {
+        name: "ukulele",
+        tuning: "G4 C4 E4 A4",
+        max_fret: 12,
+        string_starts: &[],
+        min_strings: 3,
+        max_strings: None,
+        min_chord_notes: None,
+        root_in_bass: false,
+        extra_low_strings: 0,
+        scale_length: 346.0,
+    },
+    // Double strung courses are fretted together, so each pair counts as a single string.
+    Instrument {
+        name: "mandolin",
+        tuning: "G3 D4 A4 E5",
+        max_fret: 12,
+        string_starts: &[],
+        min_strings: 3,
+        max_strings: None,
+        min_chord_notes: None,
+        root_in_bass: false,
+        extra_low_strings: 0,
+        scale_length: 350.0,
+    },
+    // Open G (gDGBD) with the short fifth string first, as it sits next to the fourth string.
+    // It's a high drone that starts at the 5th fret.
+    Instrument {
+        name: "banjo",
+        tuning: "G4 D3 G3 B3 D4",
+        max_fret: 12,
+        string_starts: &[5],
+        min_strings: 4,
+        max_strings: None,
+        min_chord_notes: None,
+        root_in_bass: false,
+        extra_low_strings: 0,
+        scale_length: 673.0,
+    },
+    // Bass chords are two or three note voicings over the root, a full chord is too muddy that
+    // low. Frets are spread wider apart, so search further up the neck instead of stretching.
+    Instrument {
+        name: "bass",
+        tuning: "E1 A1 D2 G2",
+        max_fret: 12,
+        string_starts: &[],
+        min_strings: 2,
+        max_strings: Some(3),
+        min_chord_notes: Some(2),
+        root_in_bass: true,
+        extra_low_strings: 0,
+        scale_length: 864.0,
+    },
+    Instrument {
+        name: "bass-5",
+        tuning: "B0 E1 A1 D2 G2",
+        max_fret: 12,
+        string_starts: &[],
+        min_strings: 2,
+        max_strings: Some(3),
+        min_chord_notes: Some(2),
+        root_in_bass: true,
+        extra_low_strings: 0,
+        scale_length: 864.0,
+    },
+];
+
+/// Look up an instrument by its name, ignoring case.
+pub fn instrument(name: &str) -> Option<&'static Instrument> {
+    INSTRUMENTS
+        .iter()
+        .find(|i| i.name.eq_ignore_ascii_case(name))
+}
@@ -0,0 +1,75 @@
+//! Naming voicings by the chord tone sounding lowest.
+
+use crate::{Chord, Note, ParseError, Pitch};
+use serde::Serialize;
+use std::{fmt, str::FromStr};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Which chord tone is in the bass of a voicing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, EnumIter)]
+#[serde(rename_all = "lowercase")]
+pub enum Inversion {
+    /// The root, called root position.
+    Root,
+    /// The third, or the suspended tone of a sus chord.
+    First,
+    /// The fifth.
+    Second,
+    /// The seventh, or the sixth of a sixth chord.
+    Third,
+    /// An extension like a ninth or eleventh.
+    Extension,
+}
+
+impl Inversion {
+    /// The inversion of a voicing sounding `pitches`, from its lowest sounding chord tone. A
+    /// slash chord's bass note outside the chord is skipped. `None` if no chord tone sounds.
+    pub fn of(root: Note, chord: Chord, pitches: &[Option<Pitch>]) -> Option<Inversion> {
+        let tones = chord.tones();
+        let lowest = pitches
+            .iter()
+            .flatten()
+            .filter(|p| tones.iter().any(|t| root + t.semitones == p.note()))
+            .min()?;
+        let tone = tones.iter().find(|t| root + t.semitones == lowest.note())?;
+        let has_third = tones.iter().any(|t| t.degree == 3);
+        Some(match tone.degree {
+            1 | 8 => Inversion::Root,
+            3 => Inversion::First,
+            // a sus chord's 2nd or 4th stands in for the third
+            2 | 4 if !has_third => Inversion::First,
+            5 => Inversion::Second,
+            6 | 7 => Inversion::Third,
+            _ => Inversion::Extension,
+        })
+    }
+
+    /// Lowercase name, e.g. `"first"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Inversion::Root => "root",
+            Inversion::First => "first",
+            Inversion::Second => "second",
+            Inversion::Third => "third",
+            Inversion::Extension => "extension",
+        }
+    }
+}
+
+impl FromStr for Inversion {
+    type Err = ParseError;
+
+    /// Parse a [`Inversion::name`], case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Inversion::iter()
+            .find(|i| i.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseError::UnknownInversion(s.to_string()))
+    }
+}
+
+impl fmt::Display for Inversion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
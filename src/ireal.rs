@@ -0,0 +1,84 @@
+//! Writing chord sheets as iReal Pro charts, to practise along to them.
+
+use crate::{chord_spelling, ChordSheet, ChordSymbol};
+
+/// An `irealbook://` link that opens `sheet` in iReal Pro as a chart titled `title`, by
+/// `composer`, played in `style` like "Medium Swing". The key is the first chord's, minor if
+/// it's a minor chord. Qualities iReal Pro doesn't have, like custom ones, keep their own
+/// symbols.
+pub fn ireal_url(sheet: &ChordSheet, title: &str, composer: &str, style: &str) -> String {
+    let first = sheet.changes.iter().find_map(|c| c.symbol);
+    let key = first.map_or("C".to_string(), |s| {
+        let root = s.root.spelled(chord_spelling(s.root, s.chord));
+        let minor = s.chord.symbol().starts_with('m') && !s.chord.symbol().starts_with("maj");
+        format!("{}{}", root, if minor { "-" } else { "" })
+    });
+    let time = match sheet.beats_per_bar {
+        beats @ 2..=7 => format!("T{}4", beats),
+        _ => "T44".to_string(),
+    };
+    let bars = sheet.bars();
+    let mut chart = format!("[{}", time);
+    for (i, chords) in bars.iter().enumerate() {
+        if i > 0 {
+            chart.push('|');
+        }
+        if i > 0 && bars[i - 1] == *chords {
+            chart.push_str("x ");
+            continue;
+        }
+        for symbol in chords {
+            match symbol {
+                Some(symbol) => chart.push_str(&ireal_chord(*symbol)),
+                None => chart.push('n'),
+            }
+            chart.push(' ');
+        }
+    }
+    if bars.is_empty() {
+        chart.push_str("n ");
+    }
+    chart.push('Z');
+    let fields = [title, composer, style, &key, "n", &chart];
+    let fields: Vec<String> = fields.iter().map(|f| encode(f)).collect();
+    format!("irealbook://{}", fields.join("="))
+}
+
+/// `symbol` the way iReal Pro writes chords, like `D-7`, `C^7` or `Bh7`.
+fn ireal_chord(symbol: ChordSymbol) -> String {
+    let spelling = chord_spelling(symbol.root, symbol.chord);
+    let quality = match symbol.chord.symbol() {
+        "m" => "-",
+        "aug" => "+",
+        "dim" => "o",
+        "maj7" => "^7",
+        "m7" => "-7",
+        "sus2" => "2",
+        "sus4" => "sus",
+        "mmaj7" => "-^7",
+        "dim7" => "o7",
+        "maj9" => "^9",
+        "m9" => "-9",
+        "m6" => "-6",
+        "6/9" => "69",
+        "m7b5" => "h7",
+        other => other,
+    };
+    let mut chord = format!("{}{}", symbol.root.spelled(spelling), quality);
+    if let Some(bass) = symbol.bass {
+        chord = format!("{}/{}", chord, bass.spelled(spelling));
+    }
+    chord
+}
+
+/// `s` percent-encoded for a link, all but letters, digits and `-_.~`.
+fn encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,114 @@
+//! Writing voicings as LaTeX chord diagram macros.
+
+use crate::diagram::{barre_ends, diagram_frets, finger_label};
+use crate::Voicing;
+
+/// A `\chordscheme` diagram of `v` named `name` for the guitarchordschemes package, with the
+/// strings running down from the lowest on the left like [`ascii_diagram`](crate::ascii_diagram).
+/// Strings are numbered from 1 on the right, frets from 1 at the top of the diagram, which is
+/// labelled with its fret further up the neck.
+pub fn chordscheme(v: &Voicing, name: &str) -> String {
+    let frets = diagram_frets(v);
+    let base = *frets.start();
+    let strings = v.frets.len();
+    // numbered like the package does, from the string drawn on the right
+    let string = |i: usize| strings - i;
+    let barre = v
+        .barre
+        .as_ref()
+        .and_then(|b| barre_ends(v, b.fret).map(|ends| (b.fret, ends)));
+
+    let mut keys = vec![format!("name = {}", escape(name))];
+    if strings != 6 {
+        keys.push(format!("strings = {}", strings));
+    }
+    if frets.len() > 4 {
+        keys.push(format!("frets = {}", frets.len()));
+    }
+    if base > 1 {
+        keys.push(format!("position = {}fr", base));
+    }
+    let mut fingers = Vec::new();
+    let (mut open, mut muted) = (Vec::new(), Vec::new());
+    for (i, f) in v.frets.iter().enumerate() {
+        let finger = v.fingers.as_ref().and_then(|h| h[i]);
+        match f.0 {
+            None => muted.push(string(i).to_string()),
+            Some(0) => open.push(string(i).to_string()),
+            // the strings of a barre are drawn by it
+            Some(fret)
+                if barre.is_some_and(|(at, (first, last))| {
+                    fret == at && finger == Some(1) && (first..=last).contains(&i)
+                }) => {}
+            Some(fret) => {
+                let mut finger_key = format!("{}/{}", fret - base + 1, string(i));
+                if let Some(finger) = finger {
+                    finger_key.push(':');
+                    finger_key.push(finger_label(finger));
+                }
+                fingers.push(finger_key);
+            }
+        }
+    }
+    if !fingers.is_empty() {
+        keys.push(format!("finger = {{{}}}", fingers.join(", ")));
+    }
+    if let Some((fret, (first, last))) = barre {
+        keys.push(format!(
+            "barre = {{{}/{}-{}:1}}",
+            fret - base + 1,
+            string(first),
+            string(last)
+        ));
+    }
+    if !open.is_empty() {
+        keys.push(format!("open = {{{}}}", open.join(", ")));
+    }
+    if !muted.is_empty() {
+        keys.push(format!("mute = {{{}}}", muted.join(", ")));
+    }
+    format!("\\chordscheme[\n  {}\n]", keys.join(",\n  "))
+}
+
+/// A `\gtab` diagram of `v` named `name` for the songs package, like `\gtab{C}{X32010:032010}`:
+/// the fret the diagram starts at if it isn't the nut, then a character for each string from the
+/// one drawn on the left, `X` for muted, `0` for open and otherwise the fret counting from the
+/// top of the diagram, then the finger holding each string, `0` for none.
+pub fn gtab(v: &Voicing, name: &str) -> String {
+    let base = *diagram_frets(v).start();
+    let strings: String = v
+        .frets
+        .iter()
+        .map(|f| match f.0 {
+            None => 'X',
+            Some(0) => '0',
+            Some(fret) => char::from_digit(u32::from(fret - base + 1), 36).unwrap_or('?'),
+        })
+        .collect();
+    let fingers: String = (0..v.frets.len())
+        .map(|i| {
+            v.fingers
+                .as_ref()
+                .and_then(|h| h[i])
+                .map_or('0', finger_label)
+        })
+        .collect();
+    let position = if base > 1 {
+        format!("{}:", base)
+    } else {
+        String::new()
+    };
+    format!("\\gtab{{{}}}{{{}{}:{}}}", name, position, strings, fingers)
+}
+
+/// Escape the characters TeX treats specially.
+fn escape(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '#' | '$' | '%' | '&' | '_' | '{' | '}' => format!("\\{}", c),
+            '\\' => "\\textbackslash{}".to_string(),
+            '^' | '~' => format!("\\{}{{}}", c),
+            _ => c.to_string(),
+        })
+        .collect()
+}
@@ -0,0 +1,1379 @@
+//! Generate chord fingerings for any root, chord quality and tuning of a fretted instrument.
+//!
+//! The main entry point is [`generate_voicings`], which returns every fingering of a chord that
+//! passes the filters enabled in [`Options`], best-scoring first.
+//!
+//! ```
+//! use chord_generator::{generate_voicings, Chord, Note, Options, DEFAULT_TUNING};
+//!
+//! let voicings = generate_voicings(Note::C, Chord::MAJOR, DEFAULT_TUNING, &Options::default());
+//! assert!(!voicings.is_empty());
+//! ```
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
+use serde::Serialize;
+use std::{
+    fmt,
+    ops::{Add, RangeInclusive},
+    str::FromStr,
+};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+mod analysis;
+mod barre;
+mod book;
+mod cache;
+mod chord;
+mod chords_db;
+mod config;
+mod coverage;
+mod diagram;
+mod difficulty;
+mod diversity;
+mod doubling;
+mod drop_voicing;
+mod guitar_pro;
+mod hand;
+mod identify;
+mod instrument;
+mod inversion;
+mod ireal;
+mod latex;
+mod lilypond;
+mod lyrics;
+mod midi;
+mod musicxml;
+mod neck;
+mod note_set;
+mod pdf;
+mod pitch;
+mod reference;
+mod scorer;
+mod shape;
+mod sort_order;
+mod spelling;
+mod spread;
+mod stats;
+mod svg;
+mod symbol;
+mod synth;
+mod tab;
+mod tensions;
+mod texture;
+mod top_note;
+mod tuning;
+mod vexchords;
+mod weights;
+
+pub use analysis::{midi_chords, ChordChange, ChordSheet};
+pub use barre::Barre;
+pub use book::{chord_book, BookChord, BookLayout};
+pub use cache::Cache;
+pub use chord::{parse_formula, Chord, Quality, Tone, QUALITIES};
+pub use chords_db::{chords_db_key, chords_db_suffix, ChordsDbPosition};
+pub use config::Config;
+pub use coverage::{eliminated_by, rejected_by, relax_until_found, Filter, Relaxation};
+pub use diagram::{ascii_diagram, first_diagram_fret};
+pub use difficulty::Difficulty;
+pub use diversity::{diverse, shape_distance, MIN_DISTANCE};
+pub use doubling::{adjacent_unisons, Doubling};
+pub use drop_voicing::DropVoicing;
+pub use guitar_pro::{gp5_file, GP5_STRINGS};
+pub use hand::Hand;
+pub use identify::{containing, identify, identify_fuzzy, Candidate};
+pub use instrument::{instrument, Instrument, INSTRUMENTS};
+pub use inversion::Inversion;
+pub use ireal::ireal_url;
+pub use latex::{chordscheme, gtab};
+pub use lilypond::fret_diagram;
+pub use lyrics::{chords_over_lyrics, LyricSheet};
+pub use midi::midi_file;
+pub use musicxml::harmony;
+pub use neck::{first_position_frets, fret_position, reach};
+pub use note_set::NoteSet;
+pub use pitch::Pitch;
+pub use reference::{Reference, REFERENCES, REFERENCE_TUNING};
+pub use scorer::{fingering_score, voicing_score, Score, VoicingScorer};
+pub use shape::Shape;
+pub use sort_order::SortOrder;
+pub use spelling::{
+    chord_spelling, key_spelling, spell_chord, spell_pitches, tuning_spelling, Spelling,
+};
+pub use spread::{low_interval_limit, within_low_interval_limits, Spacing, Spread};
+pub use stats::Stats;
+pub use svg::svg_diagram;
+pub use symbol::{parse_quality, parse_quality_in, split_note, ChordSymbol};
+pub use synth::{chord_samples, pluck, Strum, SAMPLE_RATE};
+pub use tab::{fret_string, parse_fret_string, FretPattern, StringPattern};
+pub use tensions::{chord_tensions, AddedTension, Tensions};
+pub use texture::Texture;
+pub use top_note::TopNote;
+pub use tuning::{
+    has_octaves, parse_tuning, place_near, tuning_preset, Tuning, TuningPreset, DEFAULT_TUNING,
+    TUNING_PRESETS,
+};
+pub use vexchords::{VexBarre, VexChart, VexString};
+pub use weights::Weights;
+
+/// Highest fret considered on any string unless [`Options::max_fret`] says otherwise.
+pub const MAX_FRETS: u8 = 9;
+
+/// Highest fret on the longest necks, the limit for [`Options::max_fret`].
+pub const NECK_FRETS: u8 = 24;
+
+/// A pitch class, spelled with sharps. Flat spellings are accepted when parsing.
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    FromPrimitive,
+    ToPrimitive,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    EnumIter,
+    PartialOrd,
+    Ord,
+)]
+pub enum Note {
+    C = 0,
+    CSharp,
+    D,
+    DSharp,
+    E,
+    F,
+    FSharp,
+    G,
+    GSharp,
+    A,
+    ASharp,
+    B,
+}
+
+impl Note {
+    /// Conventional name of the note, e.g. `"C#"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Note::C => "C",
+            Note::CSharp => "C#",
+            Note::D => "D",
+            Note::DSharp => "D#",
+            Note::E => "E",
+            Note::F => "F",
+            Note::FSharp => "F#",
+            Note::G => "G",
+            Note::GSharp => "G#",
+            Note::A => "A",
+            Note::ASharp => "A#",
+            Note::B => "B",
+        }
+    }
+}
+
+impl FromStr for Note {
+    type Err = ParseError;
+
+    /// Parse a note name with any sharps or flats like `"C"`, `"F#"`, `"Eb"` or `"B#"`, or a
+    /// variant name like `"FSharp"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match split_note(s) {
+            Some((note, "")) => Ok(note),
+            _ => Note::iter()
+                .find(|n| format!("{:?}", n).eq_ignore_ascii_case(s))
+                .ok_or_else(|| ParseError::UnknownNote(s.to_string())),
+        }
+    }
+}
+
+impl Add<u8> for Note {
+    type Output = Self;
+
+    /// Transpose up by `rhs` semitones, wrapping around the octave.
+    fn add(self, rhs: u8) -> Self::Output {
+        FromPrimitive::from_u16((ToPrimitive::to_u16(&self).unwrap() + rhs as u16) % 12).unwrap()
+    }
+}
+
+impl Add<Finger> for Note {
+    type Output = Option<Self>;
+
+    /// The note sounded by fretting an open string tuned to `self`, or `None` if muted.
+    fn add(self, rhs: Finger) -> Self::Output {
+        rhs.0.map(|val| self + val)
+    }
+}
+
+/// Error returned when parsing notes, chord qualities or config from text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    UnknownNote(String),
+    UnknownChord(String),
+    UnknownInversion(String),
+    UnknownDropVoicing(String),
+    UnknownSpacing(String),
+    /// A difficulty that isn't beginner, intermediate or advanced.
+    UnknownDifficulty(String),
+    /// A sort order that isn't one of the [`SortOrder`] names.
+    UnknownSortOrder(String),
+    /// A tuning string without any notes in it.
+    InvalidTuning(String),
+    /// A chord formula that isn't a list of scale degrees starting at the root.
+    InvalidFormula(String),
+    /// A custom chord quality named like one that's already defined.
+    ChordExists(String),
+    /// A config file that isn't valid TOML or has unknown settings, with the reason.
+    InvalidConfig(String),
+    /// A fret string like `x32010` with something other than frets and `x` in it.
+    InvalidFretString(String),
+    /// A strum that isn't one of the [`Strum`] names.
+    UnknownStrum(String),
+    /// A file that isn't a Standard MIDI File, with the reason.
+    InvalidMidi(String),
+    /// A bar of a chord chart that's `%` with no bar before it, or has more chords than beats.
+    InvalidChart(String),
+    /// A fret pattern like `x 3-5 * * 0 x` with something other than frets, ranges of them, `x`
+    /// and `*` in it.
+    InvalidFretPattern(String),
+    /// A texture that isn't one of the [`Texture`] names.
+    UnknownTexture(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownNote(s) => write!(f, "unknown note '{}'", s),
+            ParseError::UnknownChord(s) => write!(f, "unknown chord quality '{}'", s),
+            ParseError::UnknownInversion(s) => write!(f, "unknown inversion '{}'", s),
+            ParseError::UnknownDropVoicing(s) => write!(f, "unknown drop voicing '{}'", s),
+            ParseError::UnknownSpacing(s) => write!(f, "unknown voicing spacing '{}'", s),
+            ParseError::UnknownDifficulty(s) => write!(f, "unknown difficulty '{}'", s),
+            ParseError::UnknownSortOrder(s) => write!(f, "unknown sort order '{}'", s),
+            ParseError::InvalidTuning(s) => write!(f, "tuning '{}' has no strings", s),
+            ParseError::InvalidFormula(s) => write!(f, "invalid chord formula '{}'", s),
+            ParseError::ChordExists(s) => write!(f, "chord quality '{}' already exists", s),
+            ParseError::InvalidConfig(s) => write!(f, "invalid config: {}", s),
+            ParseError::InvalidFretString(s) => write!(f, "invalid fret string '{}'", s),
+            ParseError::UnknownStrum(s) => write!(f, "unknown strum '{}'", s),
+            ParseError::InvalidMidi(s) => write!(f, "invalid MIDI file: {}", s),
+            ParseError::InvalidChart(s) => write!(f, "invalid bar '{}' in chord chart", s),
+            ParseError::InvalidFretPattern(s) => write!(f, "invalid fret pattern '{}'", s),
+            ParseError::UnknownTexture(s) => write!(f, "unknown texture '{}'", s),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The fret held on a single string: `Some(0)` is open, `None` is muted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Finger(pub Option<u8>);
+
+impl From<Finger> for char {
+    /// `x` for muted, otherwise the fret as a digit, counting on in letters from `a` for 10 as
+    /// there's only one character for it. See [`fret_string`] for writing frets unambiguously.
+    fn from(f: Finger) -> char {
+        match f.0 {
+            None => 'x',
+            Some(v) => char::from_digit(v as u32, 36).unwrap_or('?'),
+        }
+    }
+}
+
+impl From<Finger> for i8 {
+    fn from(f: Finger) -> i8 {
+        match f.0 {
+            None => -1,
+            Some(v) => v as i8,
+        }
+    }
+}
+
+impl Serialize for Finger {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_i8(i8::from(*self))
+    }
+}
+
+/// One [`Finger`] per string, in the same order as the [`Tuning`].
+pub type Fingering = Vec<Finger>;
+
+/// A fingering of a chord together with the pitches it sounds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Voicing {
+    /// Fret held on each string.
+    pub frets: Fingering,
+    /// The frets as a [`fret_string`], like `x32010`.
+    pub tab: String,
+    /// The fret a diagram of the voicing starts at, see [`first_diagram_fret`].
+    pub base_fret: u8,
+    /// Fret held on each string counting from 1 at the `base_fret`, where a diagram draws it.
+    /// Open and muted strings are left as they are.
+    pub relative_frets: Fingering,
+    /// Pitch sounded by each string as a MIDI note number, `None` for muted strings.
+    pub pitches: Vec<Option<Pitch>>,
+    /// Frequency each string sounds in hertz, to the hundredth, with A4 at the
+    /// [`Options::reference_pitch`]. `None` for muted strings.
+    pub frequencies: Vec<Option<f64>>,
+    /// Name of the note each string sounds, see [`spell_pitches`].
+    pub notes: Vec<Option<&'static str>>,
+    /// The interval each string sounds above the root, see [`Chord::interval`].
+    pub intervals: Vec<Option<String>>,
+    /// The chord tone in the bass, see [`Inversion::of`].
+    pub inversion: Option<Inversion>,
+    /// Whether four notes are stacked closely or as a drop voicing, see [`DropVoicing::of`].
+    pub drop: Option<DropVoicing>,
+    /// How far apart the sounding pitches are.
+    pub spread: Spread,
+    /// Whether the root isn't played at all, so the voicing relies on a bass player for it.
+    pub rootless: bool,
+    /// String sounding the lowest root, numbered from 1 for the highest like
+    /// [`Options::strings`], the way method books group shapes into root-on-6, root-on-5 and so
+    /// on. `None` for rootless voicings.
+    pub root_string: Option<u8>,
+    /// How many strings play each chord tone.
+    pub doubling: Doubling,
+    /// How many pairs of neighbouring strings sound the same pitch, see [`adjacent_unisons`].
+    pub unisons: usize,
+    /// The chord tones it plays and leaves out, and the tensions it could add.
+    pub tensions: Tensions,
+    /// How its pitches are laid out, see [`Texture::of`].
+    pub textures: Vec<Texture>,
+    /// How the built-in [`voicing_score`] adds up.
+    pub score: Score,
+    /// Finger holding each string, see [`Hand::fingers`]. `None` if it takes more than four.
+    pub fingers: Option<Vec<Option<u8>>>,
+    /// The barre the fingering is held with, if any.
+    pub barre: Option<Barre>,
+    /// How hard the voicing is to play.
+    pub difficulty: Difficulty,
+}
+
+impl Voicing {
+    /// Build the voicing of `frets` for `chord` on `root` in tuning `t`, see
+    /// [`get_played_pitches`], with fingers assigned the way `options` allow.
+    pub fn new(
+        root: Note,
+        chord: Chord,
+        t: &[Pitch],
+        frets: Fingering,
+        options: &Options,
+    ) -> Voicing {
+        let pitches = get_played_pitches(t, &frets, &options.string_starts);
+        let frequencies = pitches
+            .iter()
+            .map(|p| {
+                p.map(|p| {
+                    (f64::from(p.frequency_at(options.reference_pitch)) * 100.0).round() / 100.0
+                })
+            })
+            .collect();
+        let notes = spell_pitches(root, chord, &pitches);
+        let base_fret = diagram::base_fret(&frets);
+        let intervals = pitches
+            .iter()
+            .map(|p| p.map(|p| chord.interval(root, p.note())))
+            .collect();
+        let inversion = Inversion::of(root, chord, &pitches);
+        let drop = DropVoicing::of(&pitches);
+        let spread = Spread::of(&pitches);
+        let rootless = !pitches.iter().flatten().any(|p| p.note() == root);
+        let root_string = pitches
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| p.filter(|p| p.note() == root).map(|p| (p, i)))
+            .min()
+            .map(|(_, i)| (frets.len() - i) as u8);
+        let doubling = Doubling::of(root, chord, &pitches);
+        let unisons = adjacent_unisons(&pitches);
+        let tensions = Tensions::of(root, chord, t, &frets, &pitches, options);
+        let textures = Texture::of(root, chord, &pitches);
+        let hand = Hand::of(&frets, options.thumb);
+        let difficulty = Difficulty::of(&frets, hand.as_ref(), options.scale_length);
+        let barre = hand.as_ref().and_then(|h| h.barre.clone());
+        let fingers = hand.map(|h| h.fingers);
+        let score = Score::of(&frets, &doubling, unisons, options);
+        Voicing {
+            tab: fret_string(&frets),
+            base_fret,
+            relative_frets: frets
+                .iter()
+                .map(|f| Finger(f.0.map(|fret| if fret == 0 { 0 } else { fret - base_fret + 1 })))
+                .collect(),
+            frets,
+            pitches,
+            frequencies,
+            notes,
+            intervals,
+            inversion,
+            drop,
+            spread,
+            rootless,
+            root_string,
+            doubling,
+            unisons,
+            tensions,
+            textures,
+            score,
+            fingers,
+            barre,
+            difficulty,
+        }
+    }
+
+    /// The lowest sounding pitch, which isn't always on the lowest string in re-entrant tunings.
+    pub fn bass(&self) -> Option<Pitch> {
+        self.pitches.iter().flatten().min().copied()
+    }
+
+    /// The highest sounding pitch.
+    pub fn top(&self) -> Option<Pitch> {
+        self.pitches.iter().flatten().max().copied()
+    }
+
+    /// Reverse the string order, for left-handed players.
+    pub fn mirror(&mut self) {
+        self.frets.reverse();
+        self.tab = fret_string(&self.frets);
+        self.relative_frets.reverse();
+        self.pitches.reverse();
+        self.frequencies.reverse();
+        self.notes.reverse();
+        self.intervals.reverse();
+        if let Some(fingers) = &mut self.fingers {
+            fingers.reverse();
+        }
+    }
+}
+
+/// Search range and filters used by [`generate_voicings`]. The default enables all the filters
+/// with the limits used for standard guitar.
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Highest fret considered on any string, up to [`NECK_FRETS`].
+    pub max_fret: u8,
+    /// Fret at which each string's fretboard begins, e.g. 5 for the short fifth string of a banjo.
+    /// Such a string sounds its open note at that fret, and can only be fretted above it. Frets
+    /// are still numbered along the neck. Missing entries mean the string runs the full neck.
+    pub string_starts: Vec<u8>,
+    /// Fret the capo is placed at, 0 for no capo. Fingerings only use the frets above the capo,
+    /// and are numbered relative to it, so 0 is a string played at the capo.
+    pub capo: u8,
+    /// Restrict every fretted note to this window of frets, e.g. `5..=9` to play around the 7th
+    /// position. Open strings are still allowed, but no longer favoured by the score.
+    pub position: Option<RangeInclusive<u8>>,
+    /// Only play this set of adjacent strings, all of them, muting the others. Strings are
+    /// numbered the way guitarists count them, from 1 for the highest one, so `2..=5` is ADGB on
+    /// a guitar.
+    pub strings: Option<RangeInclusive<u8>>,
+    /// `Some(true)` only keeps fingerings playing an open string, for open chords, and
+    /// `Some(false)` rejects them, for movable shapes that can be transposed up and down the neck.
+    pub open_strings: Option<bool>,
+    /// Reject fingerings playing fewer than this many strings.
+    pub min_strings: usize,
+    /// Reject fingerings playing more than this many strings.
+    pub max_strings: Option<usize>,
+    /// Accept fingerings sounding only this many different chord notes instead of all of them.
+    pub min_chord_notes: Option<usize>,
+    /// Reject fingerings whose lowest sounding note isn't the root.
+    pub root_in_bass: bool,
+    /// Note that has to sound lowest, for slash chords like D/F#. It doesn't have to be part of
+    /// the chord, and if it isn't, it may only be played in the bass.
+    pub bass: Option<Note>,
+    /// Only keep voicings in this inversion.
+    pub inversion: Option<Inversion>,
+    /// Only keep voicings with their lowest root on this string, see [`Voicing::root_string`].
+    pub root_string: Option<u8>,
+    /// Only keep four-note voicings spread out like this.
+    pub drop: Option<DropVoicing>,
+    /// Only keep voicings within an octave, or only wider ones.
+    pub spacing: Option<Spacing>,
+    /// Reject voicings with more than this many semitones between adjacent voices.
+    pub max_gap: Option<u8>,
+    /// Reject voicings with intervals too close for their register, see [`low_interval_limit`].
+    pub low_interval_limits: bool,
+    /// Reject voicings leaving out the third, so they state whether the chord is major or minor.
+    /// Chords without a third, like sus chords, are unaffected.
+    pub require_third: bool,
+    /// Reject voicings breaking the doubling rules, see [`Doubling::is_strict`].
+    pub strict_doubling: bool,
+    /// Reject voicings with neighbouring strings sounding the same pitch, see
+    /// [`adjacent_unisons`].
+    pub no_unisons: bool,
+    /// Only keep voicings with all of these textures.
+    pub textures: Vec<Texture>,
+    /// Reject voicings with any of these textures.
+    pub excluded_textures: Vec<Texture>,
+    /// Reject voicings harder to play than this.
+    pub max_difficulty: Option<Difficulty>,
+    /// Note that has to sound highest, like the melody in a chord-melody arrangement.
+    pub top: Option<TopNote>,
+    /// How many of the lowest strings extend the range below a six string guitar. Playing them
+    /// lowers the score, so voicings don't get muddy unless nothing else works.
+    pub extra_low_strings: usize,
+    /// Reject fingerings spread across more than [`Options::max_span`] frets.
+    pub compact: bool,
+    /// Most frets the fretted notes of a fingering should cover in first position, e.g. 3 for
+    /// beginners or 5 for stretches. Further up the neck, the same stretch covers more frets.
+    /// Wider fingerings score lower even when [`Options::compact`] is off.
+    pub max_span: u8,
+    /// Distance from the nut to the bridge, in millimetres, for working out stretches.
+    pub scale_length: f32,
+    /// Frequency of A4 in hertz, that [`Voicing::frequencies`] are worked out from.
+    pub reference_pitch: f32,
+    /// Reject fingerings with muted strings between played strings.
+    pub contiguous: bool,
+    /// Relax [`Options::contiguous`] to allow muted strings between played strings, as long as a
+    /// finger fretting a neighbouring string can damp them, see [`can_damp_mutes`].
+    pub interior_mutes: bool,
+    /// Reject fingerings that can't be held with four fingers and an optional barre.
+    pub four_fingered: bool,
+    /// Let the thumb wrap over the neck to fret the lowest string, see [`Hand::of`].
+    pub thumb: bool,
+    /// Only generate shell voicings, the root, third and seventh on three strings, muting the
+    /// others wherever they are. See [`Chord::shell_notes`].
+    pub shell: bool,
+    /// Only generate rootless voicings, leaving the root to a bass player. See
+    /// [`Chord::rootless_tones`]. Ignored for shell voicings.
+    pub rootless: bool,
+    /// How much each part of a fingering counts towards its score.
+    pub weights: Weights,
+    /// What to list the voicings by.
+    pub sort: SortOrder,
+    /// Only keep this many voicings, the first ones in [`Options::sort`] order. The others are
+    /// dropped as generation goes, so memory stays bounded on big runs.
+    pub limit: Option<usize>,
+    /// Keep [`Options::limit`] voicings that differ from each other instead of the very first
+    /// ones, which are often the same shape a fret apart, see [`diverse`]. Every voicing is kept
+    /// until they're picked.
+    pub diverse: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            max_fret: MAX_FRETS,
+            string_starts: Vec::new(),
+            capo: 0,
+            position: None,
+            strings: None,
+            open_strings: None,
+            min_strings: 4,
+            max_strings: None,
+            min_chord_notes: None,
+            root_in_bass: false,
+            bass: None,
+            inversion: None,
+            root_string: None,
+            drop: None,
+            spacing: None,
+            max_gap: None,
+            low_interval_limits: false,
+            require_third: false,
+            strict_doubling: false,
+            no_unisons: false,
+            textures: Vec::new(),
+            excluded_textures: Vec::new(),
+            max_difficulty: None,
+            top: None,
+            extra_low_strings: 0,
+            compact: true,
+            max_span: 4,
+            scale_length: 648.0,
+            reference_pitch: 440.0,
+            contiguous: true,
+            interior_mutes: false,
+            four_fingered: true,
+            thumb: false,
+            shell: false,
+            rootless: false,
+            weights: Weights::default(),
+            sort: SortOrder::Score,
+            limit: None,
+            diverse: false,
+        }
+    }
+}
+
+/// First fret of string `i` given the per-string starts, see [`Options::string_starts`].
+fn string_start(starts: &[u8], i: usize) -> u8 {
+    starts.get(i).copied().unwrap_or(0)
+}
+
+/// The pitch sounded by `f` on a string tuned to `open` whose fretboard begins at `start`, `None`
+/// for a fret below where it begins.
+fn sounded_pitch(open: Pitch, f: Finger, start: u8) -> Option<Pitch> {
+    match f.0 {
+        Some(x) if x > 0 => x.checked_sub(start).map(|x| open + x),
+        _ => open + f,
+    }
+}
+
+/// What each string can play in a fingering of `notes`: muted, or any fret up to `max_fret` that
+/// sounds one of them, in the order the strings are searched.
+fn string_candidates(t: &[Pitch], notes: NoteSet, max_fret: u8, starts: &[u8]) -> Vec<Fingering> {
+    t.iter()
+        .enumerate()
+        .map(|(i, &open)| {
+            let start = string_start(starts, i);
+            // short strings can't be fretted below where they start
+            let frets = std::iter::once(0).chain(start + 1..=max_fret);
+            std::iter::once(Finger(None))
+                .chain(frets.map(|x| Finger(Some(x))).filter(|&f| {
+                    sounded_pitch(open, f, start).is_some_and(|p| notes.contains(p.note()))
+                }))
+                .collect()
+        })
+        .collect()
+}
+
+/// The pitch sounded on each string, `None` for muted strings. `starts` are the frets short
+/// strings begin at, as in [`Options::string_starts`].
+pub fn get_played_pitches(t: &[Pitch], fingering: &[Finger], starts: &[u8]) -> Vec<Option<Pitch>> {
+    t.iter()
+        .zip(fingering)
+        .enumerate()
+        .map(|(i, (&p, &f))| sounded_pitch(p, f, string_start(starts, i)))
+        .collect()
+}
+
+/// The note sounded on each string, `None` for muted strings.
+pub fn get_played_notes(t: &[Pitch], fingering: &[Finger], starts: &[u8]) -> Vec<Option<Note>> {
+    get_played_pitches(t, fingering, starts)
+        .into_iter()
+        .map(|p| p.map(|p| p.note()))
+        .collect()
+}
+
+/// Every fingering up to [`Options::max_fret`] that plays all the notes of the chord but the
+/// [`Chord::optional_notes`] (or [`Options::min_chord_notes`] of them) and nothing else but the
+/// [`Options::bass`] note. Fingerings outside [`Options::position`], or failing
+/// [`Options::compact`], [`Options::contiguous`] or `open_strings: Some(false)`, are pruned while
+/// searching, the other filters aren't applied.
+pub fn gen_inversions(root: Note, chord: Chord, t: &[Pitch], options: &Options) -> Vec<Fingering> {
+    Search::new(root, chord, t, options).collect()
+}
+
+/// Backtracking search for [`gen_inversions`], fretting one string at a time from the lowest and
+/// yielding each fingering of the chord as soon as it's found.
+struct Search {
+    t: Tuning,
+    options: Options,
+    candidates: Vec<Fingering>,
+    chord_notes: NoteSet,
+    /// The chord notes that can't be left out.
+    needed: NoteSet,
+    required: Option<usize>,
+    /// The partial fingering being extended.
+    fingering: Fingering,
+    /// Index of the next candidate to try on each string.
+    next: Vec<usize>,
+}
+
+impl Search {
+    fn new(root: Note, chord: Chord, t: &[Pitch], options: &Options) -> Search {
+        let (chord_notes, optional) = if options.shell {
+            match chord.shell_notes(root) {
+                Some(notes) => (notes, Vec::new()),
+                None => (Vec::new(), Vec::new()),
+            }
+        } else if options.rootless {
+            match chord.rootless_tones() {
+                Some(tones) => (
+                    tones.iter().map(|t| root + t.semitones).collect(),
+                    tones
+                        .iter()
+                        .filter(|t| t.optional)
+                        .map(|t| root + t.semitones)
+                        .collect(),
+                ),
+                None => (Vec::new(), Vec::new()),
+            }
+        } else {
+            (chord.notes(root), chord.optional_notes(root))
+        };
+        let chord_notes: NoteSet = chord_notes.into_iter().collect();
+        let optional: NoteSet = optional.into_iter().collect();
+        let bass = options.bass.map(NoteSet::of).unwrap_or_default();
+        // only the frets sounding a chord note, or the slash chord's bass, are worth trying, and
+        // there's nothing to search for chords without a shell or rootless voicing
+        let candidates = if chord_notes.is_empty() {
+            Vec::new()
+        } else {
+            string_candidates(
+                t,
+                chord_notes | bass,
+                options.max_fret,
+                &options.string_starts,
+            )
+        };
+        Search {
+            t: t.to_vec(),
+            options: options.clone(),
+            next: vec![0; candidates.len()],
+            candidates,
+            required: options.min_chord_notes.map(|n| n.min(chord_notes.len())),
+            needed: chord_notes & !optional,
+            chord_notes,
+            fingering: Vec::with_capacity(t.len()),
+        }
+    }
+
+    /// Could frets on the remaining strings still turn the partial `fingering` into a chord that
+    /// passes the filters. Each of these checks only gets worse as more strings are added.
+    fn can_complete(&self, fingering: &[Finger]) -> bool {
+        let options = &self.options;
+        let in_position = options
+            .position
+            .as_ref()
+            .is_none_or(|p| is_in_position(fingering, p));
+        let compact =
+            !options.compact || is_compact(fingering, options.max_span, options.scale_length);
+        // shells skip strings, like 3x34xx
+        let contiguous = !options.contiguous
+            || options.shell
+            || is_contiguous(fingering)
+            || (options.interior_mutes && can_damp_mutes(fingering));
+        let open = options.open_strings != Some(false) || !has_open_strings(fingering);
+        // every string left can add one more chord note at most
+        let left = self.t.len() - fingering.len();
+        let held = self
+            .played(fingering)
+            .into_iter()
+            .flatten()
+            .map(|p| p.note())
+            .collect();
+        in_position && compact && contiguous && open && self.missing(held) <= left
+    }
+
+    /// How many more chord notes have to be played when the notes `held` are.
+    fn missing(&self, held: NoteSet) -> usize {
+        match self.required {
+            Some(n) => n.saturating_sub((held & self.chord_notes).len()),
+            None => (self.needed & !held).len(),
+        }
+    }
+
+    /// Does the complete `fingering` play all (or enough) notes of the chord and nothing else,
+    /// except for a slash chord's bass note on the lowest sounding string.
+    fn is_chord(&self, fingering: &[Finger]) -> bool {
+        let played = self.played(fingering);
+        let held: NoteSet = played.iter().flatten().map(|p| p.note()).collect();
+        let others = held & !self.chord_notes;
+        let all_played_notes_valid = others.is_empty()
+            || self.options.bass.is_some_and(|bass| {
+                let lowest = played.iter().flatten().min();
+                others == NoteSet::of(bass)
+                    && played
+                        .iter()
+                        .flatten()
+                        .filter(|p| p.note() == bass)
+                        .all(|p| Some(p) == lowest)
+            });
+        all_played_notes_valid && self.missing(held) == 0
+    }
+
+    /// The pitches sounded by the strings fretted so far.
+    fn played(&self, fingering: &[Finger]) -> Vec<Option<Pitch>> {
+        get_played_pitches(
+            &self.t[..fingering.len()],
+            fingering,
+            &self.options.string_starts,
+        )
+    }
+}
+
+impl Iterator for Search {
+    type Item = Fingering;
+
+    fn next(&mut self) -> Option<Fingering> {
+        if self.candidates.is_empty() {
+            return None;
+        }
+        loop {
+            let string = self.fingering.len();
+            if string == self.candidates.len() {
+                let found = self
+                    .is_chord(&self.fingering)
+                    .then(|| self.fingering.clone());
+                self.fingering.pop();
+                if found.is_some() {
+                    return found;
+                }
+                continue;
+            }
+            let Some(&f) = self.candidates[string].get(self.next[string]) else {
+                // every fret tried on this string, back up to the one before, and stop once
+                // they're all tried on the first string
+                self.fingering.pop()?;
+                continue;
+            };
+            self.next[string] += 1;
+            self.fingering.push(f);
+            if self.can_complete(&self.fingering) {
+                if let Some(next) = self.next.get_mut(string + 1) {
+                    *next = 0;
+                }
+            } else {
+                self.fingering.pop();
+            }
+        }
+    }
+}
+
+/// Is the fingering compact (true) or a wider stretch than `max_span` frets in first position
+/// (false), on a neck of `scale` length. Higher up the neck, where frets are closer together,
+/// more of them fit in the same stretch.
+pub fn is_compact(fingering: &[Finger], max_span: u8, scale: f32) -> bool {
+    // a little slack for rounding errors
+    reach(fingering, scale) <= fret_position(scale, max_span) + 0.01
+}
+
+/// Are the played strings contiguious (true) or have random unplayed strings in between (false)
+pub fn is_contiguous(fingering: &[Finger]) -> bool {
+    let mut zone = 0;
+    // xx12xx is valid, where first xx are zone0, 12 are zone1, and last xx are zone3
+    for f in fingering {
+        if zone == 0 {
+            if f.0.is_some() {
+                zone = 1;
+            }
+            continue;
+        }
+        if zone == 1 {
+            if f.0.is_none() {
+                zone = 2;
+            }
+            continue;
+        }
+        // zone 2 may only hold more muted strings
+        if f.0.is_some() {
+            return false;
+        }
+    }
+    true
+}
+
+/// Can every muted string between played strings be damped by a finger fretting a string next to
+/// it, like the D string of x3x33x by the finger on the A string. Open strings can't damp their
+/// neighbours.
+pub fn can_damp_mutes(fingering: &[Finger]) -> bool {
+    let played = |f: &Finger| f.0.is_some();
+    let (Some(first), Some(last)) = (
+        fingering.iter().position(played),
+        fingering.iter().rposition(played),
+    ) else {
+        return true;
+    };
+    let fretted = |i: usize| matches!(fingering.get(i), Some(Finger(Some(x))) if *x > 0);
+    (first..=last).all(|i| fingering[i].0.is_some() || fretted(i - 1) || fretted(i + 1))
+}
+
+/// Are all the fretted notes inside the `position` window, open and muted strings are always fine
+pub fn is_in_position(fingering: &[Finger], position: &RangeInclusive<u8>) -> bool {
+    fingering.iter().all(|f| match f.0 {
+        Some(x) if x > 0 => position.contains(&x),
+        _ => true,
+    })
+}
+
+/// Is any string played open
+pub fn has_open_strings(fingering: &[Finger]) -> bool {
+    fingering.iter().any(|f| f.0 == Some(0))
+}
+
+/// Make sure at least `n` strings are being played, too few notes sound empty
+pub fn plays_at_least(fingering: &[Finger], n: usize) -> bool {
+    fingering.iter().filter(|f| f.0.is_some()).count() >= n
+}
+
+/// Make sure no more than `n` strings are being played
+pub fn plays_at_most(fingering: &[Finger], n: usize) -> bool {
+    fingering.iter().filter(|f| f.0.is_some()).count() <= n
+}
+
+/// Is `note` the lowest sounding note
+pub fn is_in_bass(note: Note, t: &[Pitch], fingering: &[Finger], starts: &[u8]) -> bool {
+    get_played_pitches(t, fingering, starts)
+        .into_iter()
+        .flatten()
+        .min()
+        .map(|p| p.note())
+        == Some(note)
+}
+
+/*
+Chords that can be played with 4 fingers easily with first finger optionally barring over a fret
+xx4532 is good
+224432 is good (barre with first finger)
+654776 is not good
+*/
+/// Can the fingering be held with four fingers, the first one optionally barring a fret, and the
+/// thumb on the lowest string if `thumb` is set
+pub fn is_four_fingered(fingering: &[Finger], thumb: bool) -> bool {
+    Hand::of(fingering, thumb).is_some()
+}
+
+/// A capo turns the strings into a shorter instrument tuned higher, so generate for that one
+/// instead. Returns the raised tuning and options with fret limits relative to the capo.
+pub fn apply_capo(t: &[Pitch], options: &Options) -> (Tuning, Options) {
+    let capo = options.capo;
+    let t = t.iter().map(|&p| p + capo).collect();
+    // stretches are still measured with the frets' widths from the nut, so they come out a little
+    // wider than they are above the capo
+    let options = Options {
+        capo: 0,
+        max_fret: options.max_fret.saturating_sub(capo),
+        string_starts: options
+            .string_starts
+            .iter()
+            .map(|s| s.saturating_sub(capo))
+            .collect(),
+        ..options.clone()
+    };
+    (t, options)
+}
+
+/// The tuning indices of the [`Options::strings`] `strings`, and the options for generating on
+/// an instrument of just those strings. `None` if the tuning doesn't have them.
+fn string_set(
+    t: &[Pitch],
+    options: &Options,
+    strings: &RangeInclusive<u8>,
+) -> Option<(RangeInclusive<usize>, Options)> {
+    let (first, last) = (*strings.start() as usize, *strings.end() as usize);
+    if first == 0 || first > last || last > t.len() {
+        return None;
+    }
+    // string 1 is the last one in the tuning
+    let (lo, hi) = (t.len() - last, t.len() - first);
+    let count = hi - lo + 1;
+    let sub = Options {
+        strings: None,
+        string_starts: options
+            .string_starts
+            .iter()
+            .skip(lo)
+            .take(count)
+            .copied()
+            .collect(),
+        min_strings: count,
+        max_strings: Some(count),
+        extra_low_strings: options.extra_low_strings.saturating_sub(lo),
+        ..options.clone()
+    };
+    Some((lo..=hi, sub))
+}
+
+/// Mute the strings of `t` around a voicing generated on the ones from `lo` on.
+fn pad(root: Note, chord: Chord, t: &[Pitch], options: &Options, lo: usize, v: Voicing) -> Voicing {
+    let mut frets = vec![Finger(None); lo];
+    frets.extend(v.frets);
+    frets.resize(t.len(), Finger(None));
+    Voicing::new(root, chord, t, frets, options)
+}
+
+/// Generate the voicings on [`Options::strings`] by generating for an instrument of just those
+/// strings, then muting the others.
+fn generate_on_strings(
+    root: Note,
+    chord: Chord,
+    t: &[Pitch],
+    options: &Options,
+    strings: &RangeInclusive<u8>,
+    scorer: Option<&dyn VoicingScorer>,
+) -> Vec<Voicing> {
+    let Some((range, sub)) = string_set(t, options, strings) else {
+        return Vec::new();
+    };
+    let sub = Options {
+        // the best voicings may change once they're ranked as whole voicings
+        limit: if scorer.is_some() {
+            None
+        } else {
+            options.limit
+        },
+        ..sub
+    };
+    let lo = *range.start();
+    let voicings = generate_voicings(root, chord, &t[range], &sub)
+        .into_iter()
+        .map(|v| pad(root, chord, t, options, lo, v));
+    match scorer {
+        // rank the whole voicings, not the ones on the string set
+        Some(scorer) => rank(voicings, scorer, options),
+        None => voicings.collect(),
+    }
+}
+
+/// Roughly how many bytes a voicing on `strings` strings takes, with the lists it keeps for each
+/// string and its tensions and textures.
+pub fn voicing_size(strings: usize) -> usize {
+    std::mem::size_of::<Voicing>() + strings * 128 + 256
+}
+
+/// The most voicings of each chord [`Options::limit`] can keep for `chords` chords generated at
+/// once, on `strings` strings, to take no more than `bytes` while they're ranked. At least 1.
+pub fn memory_limit(bytes: usize, strings: usize, chords: usize) -> usize {
+    // ranking holds up to twice the limit before cutting it back
+    let per_voicing = 2 * chords.max(1) * voicing_size(strings);
+    (bytes / per_voicing).max(1)
+}
+
+/// Sort `voicings` by descending score, keeping the order of equal ones, then by
+/// [`Options::sort`], and keep the first [`Options::limit`] of them, or [`diverse`] ones.
+fn rank(
+    voicings: impl Iterator<Item = Voicing>,
+    scorer: &dyn VoicingScorer,
+    options: &Options,
+) -> Vec<Voicing> {
+    // as if sorted stably by score and then by the sort order
+    let order = |(a, i, u): &(f64, usize, Voicing), (b, j, v): &(f64, usize, Voicing)| {
+        options.sort.cmp(u, v).then(b.total_cmp(a)).then(i.cmp(j))
+    };
+    // only the best voicings found so far are kept, so memory doesn't grow past twice the limit
+    let limit = match options.limit {
+        Some(limit) if !options.diverse => limit,
+        _ => usize::MAX,
+    };
+    let mut kept = Vec::new();
+    for (i, v) in voicings.enumerate() {
+        kept.push((scorer.score(&v), i, v));
+        if kept.len() >= limit.saturating_mul(2).max(1) {
+            kept.sort_unstable_by(order);
+            kept.truncate(limit);
+        }
+    }
+    kept.sort_unstable_by(order);
+    kept.truncate(limit);
+    let ranked = kept.into_iter().map(|(_, _, v)| v).collect();
+    match options.limit {
+        Some(n) if options.diverse => diverse(ranked, n),
+        _ => ranked,
+    }
+}
+
+/// The voicing of `fingering`, one of the chord's [`gen_inversions`], if it passes the rest of the
+/// filters in `options`.
+fn filtered(
+    root: Note,
+    chord: Chord,
+    t: &[Pitch],
+    options: &Options,
+    f: Fingering,
+) -> Option<Voicing> {
+    let (min_strings, max_strings) = if options.shell {
+        (3, Some(3))
+    } else {
+        let min_strings = chord
+            .min_strings()
+            .map_or(options.min_strings, |n| n.min(options.min_strings));
+        (min_strings, options.max_strings)
+    };
+    let playable = options
+        .open_strings
+        .is_none_or(|open| has_open_strings(&f) == open)
+        && plays_at_least(&f, min_strings)
+        && max_strings.is_none_or(|n| plays_at_most(&f, n))
+        && (!options.root_in_bass || is_in_bass(root, t, &f, &options.string_starts))
+        && options
+            .bass
+            .is_none_or(|bass| is_in_bass(bass, t, &f, &options.string_starts))
+        && (!options.four_fingered || is_four_fingered(&f, options.thumb));
+    if !playable {
+        return None;
+    }
+    let v = Voicing::new(root, chord, t, f, options);
+    let has_third = chord.tones().iter().any(|t| t.degree == 3);
+    let keep = options.inversion.is_none_or(|i| v.inversion == Some(i))
+        && options.root_string.is_none_or(|s| v.root_string == Some(s))
+        && options.drop.is_none_or(|d| v.drop == Some(d))
+        && options.spacing.is_none_or(|s| v.spread.spacing() == s)
+        && options.max_gap.is_none_or(|n| v.spread.largest_gap() <= n)
+        && (!options.low_interval_limits || within_low_interval_limits(&v.pitches))
+        && (!options.require_third || !has_third || v.doubling.count(3) > 0)
+        && (!options.strict_doubling || v.doubling.is_strict(chord))
+        && (!options.no_unisons || v.unisons == 0)
+        && options.textures.iter().all(|t| v.textures.contains(t))
+        && !options
+            .excluded_textures
+            .iter()
+            .any(|t| v.textures.contains(t))
+        && options.max_difficulty.is_none_or(|d| v.difficulty <= d)
+        && options
+            .top
+            .is_none_or(|top| v.top().is_some_and(|p| top.matches(root, chord, p)));
+    keep.then_some(v)
+}
+
+/// The voicings of a chord, generated one at a time as they're asked for, in search order rather
+/// than best first, see [`Voicings::iter`].
+pub struct Voicings {
+    root: Note,
+    chord: Chord,
+    source: Source,
+}
+
+/// Where [`Voicings`] come from.
+enum Source {
+    /// Searching every string.
+    Search(Search),
+    /// Searching the [`Options::strings`], then muting the other strings of the tuning.
+    Strings {
+        voicings: Box<Voicings>,
+        t: Tuning,
+        options: Options,
+        lo: usize,
+    },
+    /// The tuning doesn't have the strings asked for.
+    Empty,
+}
+
+impl Voicings {
+    /// The voicings of `chord` built on `root` in tuning `t` that pass the filters in `options`,
+    /// like [`generate_voicings`] but lazily, so taking the first few doesn't pay for generating
+    /// all of them.
+    ///
+    /// They come in search order, not best first: sorted by the lowest string, muted before open
+    /// before the frets going up, then by the next string and so on, so the first ones seldom
+    /// score best. Ranking needs every voicing, so use [`generate_voicings`] for the best ones.
+    /// [`Options::sort`] and [`Options::limit`] don't apply.
+    pub fn iter(root: Note, chord: Chord, t: &[Pitch], options: &Options) -> Voicings {
+        if options.capo > 0 {
+            let (t, options) = apply_capo(t, options);
+            return Voicings::iter(root, chord, &t, &options);
+        }
+        let source = match &options.strings {
+            Some(strings) => match string_set(t, options, strings) {
+                Some((range, sub)) => Source::Strings {
+                    lo: *range.start(),
+                    voicings: Box::new(Voicings::iter(root, chord, &t[range], &sub)),
+                    t: t.to_vec(),
+                    options: options.clone(),
+                },
+                None => Source::Empty,
+            },
+            None => Source::Search(Search::new(root, chord, t, options)),
+        };
+        Voicings {
+            root,
+            chord,
+            source,
+        }
+    }
+}
+
+impl Iterator for Voicings {
+    type Item = Voicing;
+
+    fn next(&mut self) -> Option<Voicing> {
+        let (root, chord) = (self.root, self.chord);
+        match &mut self.source {
+            Source::Search(search) => loop {
+                let f = search.next()?;
+                if let Some(v) = filtered(root, chord, &search.t, &search.options, f) {
+                    return Some(v);
+                }
+            },
+            Source::Strings {
+                voicings,
+                t,
+                options,
+                lo,
+            } => voicings
+                .next()
+                .map(|v| pad(root, chord, t, options, *lo, v)),
+            Source::Empty => None,
+        }
+    }
+}
+
+/// Generate the voicings of `chord` built on `root` in tuning `t` that pass the filters in
+/// `options`, sorted by descending [`voicing_score`] unless [`Options::sort`] says otherwise.
+pub fn generate_voicings(root: Note, chord: Chord, t: &[Pitch], options: &Options) -> Vec<Voicing> {
+    generate(root, chord, t, options, None)
+}
+
+/// Like [`generate_voicings`], but ranking the voicings with `scorer` instead.
+pub fn generate_voicings_with(
+    root: Note,
+    chord: Chord,
+    t: &[Pitch],
+    options: &Options,
+    scorer: &dyn VoicingScorer,
+) -> Vec<Voicing> {
+    generate(root, chord, t, options, Some(scorer))
+}
+
+/// The voicings of `fingerings` generated by [`generate_voicings`] with the same arguments, in the
+/// same order, without searching for them again.
+pub fn rebuild_voicings(
+    root: Note,
+    chord: Chord,
+    t: &[Pitch],
+    options: &Options,
+    fingerings: Vec<Fingering>,
+) -> Vec<Voicing> {
+    // the voicings were worked out relative to the capo
+    if options.capo > 0 {
+        let (t, options) = apply_capo(t, options);
+        return rebuild_voicings(root, chord, &t, &options, fingerings);
+    }
+    fingerings
+        .into_iter()
+        .map(|f| Voicing::new(root, chord, t, f, options))
+        .collect()
+}
+
+/// Generate the voicings, ranked by `scorer` or by the [`voicing_score`] for the `options`
+/// they're generated with, which a capo changes.
+fn generate(
+    root: Note,
+    chord: Chord,
+    t: &[Pitch],
+    options: &Options,
+    scorer: Option<&dyn VoicingScorer>,
+) -> Vec<Voicing> {
+    if options.capo > 0 {
+        let (t, options) = apply_capo(t, options);
+        return generate(root, chord, &t, &options, scorer);
+    }
+    if let Some(strings) = &options.strings {
+        return generate_on_strings(root, chord, t, options, strings, scorer);
+    }
+    rank(
+        Voicings::iter(root, chord, t, options),
+        scorer.unwrap_or(options),
+        options,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn voicings(root: Note, chord: Chord, options: &Options) -> Vec<Voicing> {
+        generate_voicings(root, chord, DEFAULT_TUNING, options)
+    }
+
+    fn sounding(v: &Voicing) -> NoteSet {
+        v.pitches.iter().flatten().map(|p| p.note()).collect()
+    }
+
+    #[test]
+    fn search_finds_only_the_chords_notes() {
+        let chord: NoteSet = Chord::SEVENTH.notes(Note::G).into_iter().collect();
+        let optional: NoteSet = Chord::SEVENTH.optional_notes(Note::G).into_iter().collect();
+        let needed = chord & !optional;
+        let found = voicings(Note::G, Chord::SEVENTH, &Options::default());
+        assert!(!found.is_empty());
+        for v in &found {
+            assert_eq!(sounding(v) & !chord, NoteSet::default(), "{}", v.tab);
+            assert_eq!(needed & !sounding(v), NoteSet::default(), "{}", v.tab);
+            assert!(plays_at_least(&v.frets, 4), "{}", v.tab);
+            assert!(is_compact(&v.frets, 4, 648.0), "{}", v.tab);
+            assert!(is_contiguous(&v.frets), "{}", v.tab);
+        }
+        let mut tabs: Vec<&str> = found.iter().map(|v| v.tab.as_str()).collect();
+        let count = tabs.len();
+        tabs.sort_unstable();
+        tabs.dedup();
+        assert_eq!(tabs.len(), count, "each voicing is found once");
+    }
+
+    #[test]
+    fn iterates_the_voicings_it_ranks() {
+        let options = Options::default();
+        let mut lazy: Vec<String> = Voicings::iter(Note::D, Chord::MINOR, DEFAULT_TUNING, &options)
+            .map(|v| v.tab)
+            .collect();
+        let mut ranked: Vec<String> = voicings(Note::D, Chord::MINOR, &options)
+            .into_iter()
+            .map(|v| v.tab)
+            .collect();
+        lazy.sort();
+        ranked.sort();
+        assert_eq!(lazy, ranked);
+    }
+
+    #[test]
+    fn filters_the_voicings() {
+        let check = |options: Options, keep: fn(&Voicing) -> bool| {
+            let found = voicings(Note::C, Chord::MAJOR, &options);
+            assert!(!found.is_empty());
+            for v in &found {
+                assert!(keep(v), "{} {:?}", v.tab, options);
+            }
+        };
+        check(
+            Options {
+                root_in_bass: true,
+                ..Options::default()
+            },
+            |v| v.pitches.iter().flatten().min().map(|p| p.note()) == Some(Note::C),
+        );
+        check(
+            Options {
+                open_strings: Some(false),
+                ..Options::default()
+            },
+            |v| !has_open_strings(&v.frets),
+        );
+        check(
+            Options {
+                position: Some(5..=8),
+                ..Options::default()
+            },
+            |v| is_in_position(&v.frets, &(5..=8)),
+        );
+        check(
+            Options {
+                max_strings: Some(4),
+                ..Options::default()
+            },
+            |v| v.frets.iter().filter(|f| f.0.is_some()).count() == 4,
+        );
+        check(
+            Options {
+                inversion: Some(Inversion::First),
+                ..Options::default()
+            },
+            |v| v.inversion == Some(Inversion::First),
+        );
+    }
+
+    #[test]
+    fn slash_chords_have_their_bass_lowest() {
+        let options = Options {
+            bass: Some(Note::FSharp),
+            ..Options::default()
+        };
+        let found = voicings(Note::D, Chord::MAJOR, &options);
+        assert!(!found.is_empty());
+        for v in &found {
+            let lowest = v.pitches.iter().flatten().min().unwrap();
+            assert_eq!(lowest.note(), Note::FSharp, "{}", v.tab);
+        }
+    }
+
+    #[test]
+    fn limits_keep_the_best_voicings() {
+        let options = Options::default();
+        let all = voicings(Note::A, Chord::MINOR, &options);
+        let limited = voicings(
+            Note::A,
+            Chord::MINOR,
+            &Options {
+                limit: Some(3),
+                ..options
+            },
+        );
+        let tabs = |v: &[Voicing]| v.iter().map(|v| v.tab.clone()).collect::<Vec<_>>();
+        assert_eq!(tabs(&limited), tabs(&all[..3]));
+    }
+}
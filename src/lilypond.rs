@@ -0,0 +1,30 @@
+//! Writing voicings as LilyPond fret diagrams.
+
+use crate::diagram::{barre_ends, diagram_frets, finger_label};
+use crate::Voicing;
+
+/// A LilyPond `\fret-diagram` markup of `v`, like `\fret-diagram "f:1;w:6;h:4;6-x;5-3-3;..."`
+/// for a C, with the strings running down from the lowest on the left like
+/// [`ascii_diagram`](crate::ascii_diagram). Strings are numbered from 1 on the right and frets
+/// counted from the nut, as LilyPond labels diagrams further up the neck itself.
+pub fn fret_diagram(v: &Voicing) -> String {
+    let strings = v.frets.len();
+    let string = |i: usize| strings - i;
+    let mut code = format!("f:1;w:{};h:{};", strings, diagram_frets(v).len());
+    if let Some(barre) = &v.barre {
+        if let Some((first, last)) = barre_ends(v, barre.fret) {
+            code += &format!("c:{}-{}-{};", string(first), string(last), barre.fret);
+        }
+    }
+    for (i, f) in v.frets.iter().enumerate() {
+        code += &match (f.0, v.fingers.as_ref().and_then(|h| h[i])) {
+            (None, _) => format!("{}-x;", string(i)),
+            (Some(0), _) => format!("{}-o;", string(i)),
+            (Some(fret), Some(finger)) => {
+                format!("{}-{}-{};", string(i), fret, finger_label(finger))
+            }
+            (Some(fret), None) => format!("{}-{};", string(i), fret),
+        };
+    }
+    format!("\\fret-diagram \"{}\"", code)
+}
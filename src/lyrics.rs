@@ -0,0 +1,77 @@
+//! Laying out songs with chords over the lyrics, the way songbook sites print them.
+
+use crate::{Chord, ChordSymbol, ParseError};
+
+/// A song laid out with [`chords_over_lyrics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LyricSheet {
+    /// Each line of lyrics with a line of its chords above it.
+    pub text: String,
+    /// The chords of the song in the order they're first played, N.C. left out.
+    pub chords: Vec<ChordSymbol>,
+}
+
+/// Lay out `song`, written in ChordPro with each chord in brackets before the syllable it's
+/// played on like `[G]Hello [Em]darling`, with its chords on a line above each line of lyrics.
+/// Chords are parsed out of `chords` like [`ChordSymbol::parse_in`]. The `{title}` and
+/// `{subtitle}` directives are printed as they are and `{comment}` ones in parentheses, the
+/// others are left out.
+pub fn chords_over_lyrics(song: &str, chords: &[Chord]) -> Result<LyricSheet, ParseError> {
+    let mut text = String::new();
+    let mut used = Vec::new();
+    for line in song.lines() {
+        let line = line.trim_end();
+        if let Some(directive) = line
+            .trim()
+            .strip_prefix('{')
+            .and_then(|d| d.strip_suffix('}'))
+        {
+            let (name, value) = directive.split_once(':').unwrap_or((directive, ""));
+            let value = value.trim();
+            match name.trim() {
+                "title" | "t" | "subtitle" | "st" => text += &format!("{}\n", value),
+                "comment" | "c" => text += &format!("({})\n", value),
+                _ => {}
+            }
+            continue;
+        }
+        let (mut above, mut lyrics) = (String::new(), String::new());
+        let mut rest = line;
+        while let Some(open) = rest.find('[') {
+            let Some(close) = rest[open..].find(']').map(|c| open + c) else {
+                break;
+            };
+            lyrics += &rest[..open];
+            let name = rest[open + 1..close].trim();
+            if name != "N.C." {
+                let symbol = ChordSymbol::parse_in(name, chords)?;
+                if !used.contains(&symbol) {
+                    used.push(symbol);
+                }
+            }
+            // a chord right after another one would run into it, so the words wait for it
+            let column = if above.is_empty() {
+                width(&lyrics)
+            } else {
+                width(&lyrics).max(width(&above) + 1)
+            };
+            lyrics += &" ".repeat(column - width(&lyrics));
+            above += &" ".repeat(column - width(&above));
+            above += name;
+            rest = &rest[close + 1..];
+        }
+        lyrics += rest;
+        if !above.is_empty() {
+            text += &format!("{}\n", above);
+        }
+        if !lyrics.trim().is_empty() || above.is_empty() {
+            text += &format!("{}\n", lyrics.trim_end());
+        }
+    }
+    Ok(LyricSheet { text, chords: used })
+}
+
+/// Columns `s` takes up in a monospaced font.
+fn width(s: &str) -> usize {
+    s.chars().count()
+}
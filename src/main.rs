@@ -1,328 +1,2428 @@
+use chord_generator::{
+    apply_capo, ascii_diagram, chord_book, chord_spelling, chords_db_key, chords_db_suffix,
+    chords_over_lyrics, chordscheme, containing, eliminated_by, first_diagram_fret, fret_diagram,
+    generate_voicings, get_played_pitches, gp5_file, gtab, harmony, has_octaves, identify,
+    identify_fuzzy, instrument, ireal_url, key_spelling, memory_limit, midi_chords, midi_file,
+    parse_fret_string, parse_quality_in, parse_tuning, place_near, rejected_by, relax_until_found,
+    spell_chord, spell_pitches, svg_diagram, tuning_preset, tuning_spelling, BookChord, BookLayout,
+    Cache, Candidate, Chord, ChordSheet, ChordSymbol, ChordsDbPosition, Config, Difficulty,
+    DropVoicing, FretPattern, Instrument, Inversion, LyricSheet, Note, NoteSet, Options,
+    ParseError, Pitch, Relaxation, Shape, SortOrder, Spacing, Stats, Texture, TopNote, Tuning,
+    TuningPreset, VexChart, Voicing, GP5_STRINGS, INSTRUMENTS, NECK_FRETS, REFERENCES,
+    REFERENCE_TUNING, TUNING_PRESETS,
+};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use itertools::Itertools;
-use num_derive::{FromPrimitive, ToPrimitive};
-use num_traits::{FromPrimitive, ToPrimitive};
-use serde::Serialize;
-use std::{collections::BTreeMap, ops::Add};
+use rayon::prelude::*;
+use serde::{Serialize, Serializer};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    io::{self, BufWriter, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
 use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
-
-const MAX_FRETS: u8 = 9;
-
-#[derive(
-    Debug,
-    Copy,
-    Clone,
-    FromPrimitive,
-    ToPrimitive,
-    PartialEq,
-    Eq,
-    Hash,
-    Serialize,
-    EnumIter,
-    PartialOrd,
-    Ord,
-)]
-enum Note {
-    C = 0,
-    CSharp,
-    D,
-    DSharp,
-    E,
-    F,
-    FSharp,
-    G,
-    GSharp,
-    A,
-    ASharp,
-    B,
-}
-
-impl Add<u8> for Note {
-    type Output = Self;
-
-    fn add(self, rhs: u8) -> Self::Output {
-        FromPrimitive::from_u8((ToPrimitive::to_u8(&self).unwrap() + rhs) % 12).unwrap()
-    }
-}
-
-impl Add<Finger> for Note {
-    type Output = Option<Self>;
-
-    fn add(self, rhs: Finger) -> Self::Output {
-        match rhs.0 {
-            None => None,
-            Some(val) => Some(self + val),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Serialize, EnumIter, PartialOrd, Ord)]
-enum Chord {
-    Major,
-    Minor,
-    Augmented,
-    Diminished,
-    Seventh,
-    MajorSeventh,
-    MinorSeventh,
-    Sus2,
-    Sus4,
-    MinorMajorSeventh,
-    DiminishedSeventh,
-    MajorNinth,
-    MinorNinth,
-    AddNinth,
-    AddEleventh,
-    MinorSixth,
-    MajorSixth,
-    AddSixthAddNinth,
-}
-
-impl Chord {
-    fn notes(&self, root: Note) -> Vec<Note> {
-        match self {
-            Chord::Major => vec![root, root + 4, root + 7],
-            Chord::Minor => vec![root, root + 3, root + 7],
-            Chord::Sus2 => vec![root, root + 2, root + 7],
-            Chord::Sus4 => vec![root, root + 5, root + 7],
-            Chord::Augmented => vec![root, root + 4, root + 8],
-            Chord::Diminished => vec![root, root + 3, root + 6],
-            Chord::MinorSixth => vec![root, root + 3, root + 7, root + 9],
-            Chord::MajorSixth => vec![root, root + 4, root + 7, root + 9],
-            Chord::Seventh => vec![root, root + 4, root + 7, root + 10],
-            Chord::MajorSeventh => vec![root, root + 4, root + 7, root + 11],
-            Chord::MinorSeventh => vec![root, root + 3, root + 7, root + 10],
-            Chord::MinorMajorSeventh => vec![root, root + 3, root + 7, root + 11],
-            Chord::DiminishedSeventh => vec![root, root + 3, root + 6, root + 9],
-            Chord::MajorNinth => vec![root, root + 4, root + 7, root + 11, root + 14],
-            Chord::MinorNinth => vec![root, root + 3, root + 7, root + 10, root + 14],
-            Chord::AddNinth => vec![root, root + 4, root + 7, root + 14],
-            Chord::AddEleventh => vec![root, root + 4, root + 7, root + 17],
-            Chord::AddSixthAddNinth => vec![root, root + 4, root + 7, root + 9, root + 14],
-        }
-    }
-}
-
-type Tuning = [Note; 6];
-const DEFAULT_TUNING: Tuning = [Note::E, Note::A, Note::D, Note::G, Note::B, Note::E];
-
-#[derive(Copy, Clone, Debug)]
-struct Finger(Option<u8>);
-
-impl Into<char> for Finger {
-    fn into(self) -> char {
-        match self.0 {
-            None => 'x',
-            Some(v) => char::from_digit(v as u32, 10).unwrap(),
-        }
-    }
-}
-
-impl Into<i8> for Finger {
-    fn into(self) -> i8 {
-        match self.0 {
-            None => -1,
-            Some(v) => v as i8,
+
+/// Generate guitar (or other fretted instrument) chord fingerings as JSON.
+#[derive(Parser, Debug)]
+#[command(version, about, subcommand_precedence_over_arg = true)]
+struct Cli {
+    /// Only generate these chords, e.g. "Cmaj7" "F#m" "Bb7" "D/F#"
+    #[arg(conflicts_with_all = ["root", "chord"])]
+    symbols: Vec<String>,
+    /// Only generate chords built on this root, e.g. "C", "F#" or "Bb"
+    #[arg(long)]
+    root: Option<Note>,
+    /// Only generate this chord quality, e.g. "maj7", "m" or "dim7"
+    #[arg(long)]
+    chord: Option<String>,
+    /// Read custom chord qualities from this TOML file
+    #[arg(long)]
+    config: Option<PathBuf>,
+    /// Instrument to generate for, e.g. "guitar" or "ukulele"
+    #[arg(long, value_parser = parse_instrument, default_value = "guitar")]
+    instrument: &'static Instrument,
+    /// Open-string notes from the lowest string, e.g. "D A D G A D" or "G4 C4 E4 A4". Without
+    /// octaves the tuning is placed in the instrument's register
+    #[arg(long, conflicts_with = "tuning_preset")]
+    tuning: Option<String>,
+    /// Use a built-in tuning, e.g. "drop-d" or "open-g"
+    #[arg(long, value_parser = parse_preset)]
+    tuning_preset: Option<&'static TuningPreset>,
+    /// Highest fret to use, defaults to what's comfortable on the instrument
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=NECK_FRETS as i64))]
+    max_fret: Option<u8>,
+    /// Only fret notes within this window, e.g. "5..9" or "5-9"
+    #[arg(long, value_parser = parse_position)]
+    position: Option<RangeInclusive<u8>>,
+    /// Only play these strings, counting from 1 for the highest, e.g. "2-5" or "1-3"
+    #[arg(long, value_parser = parse_strings)]
+    strings: Option<RangeInclusive<u8>>,
+    /// Only keep open chords, playing at least one open string
+    #[arg(long)]
+    open_only: bool,
+    /// Only keep movable shapes without open strings
+    #[arg(long, conflicts_with = "open_only")]
+    no_open: bool,
+    /// Only keep voicings playing at least this many strings, defaults to what suits the
+    /// instrument
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..), conflicts_with_all = ["strings", "shell"])]
+    min_strings: Option<u8>,
+    /// Only keep voicings playing at most this many strings
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..), conflicts_with_all = ["strings", "shell"])]
+    max_strings: Option<u8>,
+    /// Most frets a fingering may span, e.g. 3 for beginners or 5 for stretches
+    #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u8).range(1..=NECK_FRETS as i64))]
+    max_span: u8,
+    /// Distance from the nut to the bridge in millimetres, defaults to the instrument's usual
+    /// one. Frets are wider apart on longer necks, so stretches are harder
+    #[arg(long, value_parser = parse_scale_length)]
+    scale_length: Option<f32>,
+    /// Frequency of A4 in hertz that the frequencies of the strings are worked out from, e.g. 442
+    /// or 432
+    #[arg(long, default_value_t = 440.0, value_parser = parse_reference_pitch)]
+    reference_pitch: f32,
+    /// Put a capo at this fret, frets in the output are relative to it
+    #[arg(long, default_value_t = 0)]
+    capo: u8,
+    /// Only keep voicings with the root as their lowest sounding note
+    #[arg(long)]
+    root_in_bass: bool,
+    /// Only keep voicings with their lowest root on this string, numbered from 1 for the highest,
+    /// like 6 or 5 for the root-on-6 and root-on-5 shapes of a guitar
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..))]
+    root_string: Option<u8>,
+    /// Only keep voicings with this chord tone in the bass: root, first, second, third,
+    /// extension or any
+    #[arg(long, value_parser = parse_inversion, default_value = "any")]
+    inversion: AnyInversion,
+    /// Only keep four note voicings spread out like this: close, drop-2, drop-3 or drop-2&4
+    #[arg(long)]
+    drop: Option<DropVoicing>,
+    /// Only keep voicings within an octave (close) or spanning more (open)
+    #[arg(long = "voicing")]
+    spacing: Option<Spacing>,
+    /// Only keep voicings without gaps of more than this many semitones between adjacent notes
+    #[arg(long)]
+    max_gap: Option<u8>,
+    /// Reject voicings with intervals that sound muddy that low, like a third near the bottom of
+    /// a guitar
+    #[arg(long)]
+    low_interval_limits: bool,
+    /// Reject voicings leaving out the third of chords that have one
+    #[arg(long)]
+    require_third: bool,
+    /// Reject voicings tripling the root or doubling the third of a dominant chord
+    #[arg(long)]
+    strict_doubling: bool,
+    /// Reject voicings with two neighbouring strings sounding the same pitch, as one of them adds
+    /// nothing
+    #[arg(long)]
+    no_unisons: bool,
+    /// Only keep voicings with this texture, given more than once for all of them:
+    /// doubled-root, spread-triad, cluster, octave-doubled-third or wide-bass-gap
+    #[arg(long)]
+    texture: Vec<Texture>,
+    /// Reject voicings with this texture, given more than once for any of them
+    #[arg(long)]
+    without_texture: Vec<Texture>,
+    /// Only keep voicings up to this difficulty: beginner, intermediate or advanced
+    #[arg(long)]
+    max_difficulty: Option<Difficulty>,
+    /// Only keep voicings with this note on top: a pitch like "E4", a note like "E" or a chord
+    /// degree like "3rd"
+    #[arg(long)]
+    top: Option<TopNote>,
+    /// Allow muted strings between played strings when a finger on a neighbouring string can damp
+    /// them, like x3x33x
+    #[arg(long)]
+    interior_mutes: bool,
+    /// Let the thumb wrap over the neck to fret the lowest string, like the F# of a Hendrix style
+    /// D/F#
+    #[arg(long)]
+    thumb: bool,
+    /// Only generate three note shell voicings of the root, third and seventh
+    #[arg(long)]
+    shell: bool,
+    /// Only generate rootless voicings of the third, seventh and extensions, for playing with a
+    /// bassist
+    #[arg(long, conflicts_with = "shell")]
+    rootless: bool,
+    /// For chords without any voicings, play just three strings, then stretch a fret further, then
+    /// mute strings in between, until some turn up, listing what it took
+    #[arg(long)]
+    relax: bool,
+    /// List voicings by score, position, difficulty, open-strings or root-string, grouped by the
+    /// string with the lowest root
+    #[arg(long, default_value = "score")]
+    sort: SortOrder,
+    /// Only keep the first N voicings of each chord, the best scoring ones unless sorted otherwise
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+    /// Keep no more voicings of each chord than fit in MIB mebibytes while generating, lowering
+    /// --limit if it has to. --diverse keeps them all until it picks, so it can't be bounded
+    #[arg(long, value_name = "MIB", conflicts_with = "diverse")]
+    max_memory: Option<usize>,
+    /// Keep voicings that differ from each other with --limit, skipping ones a fret or two off a
+    /// better one
+    #[arg(long)]
+    diverse: bool,
+    /// Mirror the output for left-handed players, listing strings from the highest one
+    #[arg(long)]
+    left_handed: bool,
+    /// List each movable shape once, with the roots and frets it plays, instead of every voicing.
+    /// Voicings with open strings can't be moved, so they're left out
+    #[arg(long)]
+    shapes: bool,
+    /// Print the chords as json, yaml, toml or binary msgpack, or as ascii chord diagrams for
+    /// reading in the terminal, or as an html page of chord diagrams to browse offline, or as LaTeX
+    /// \chordscheme diagrams for the guitarchordschemes package or \gtab ones for the songs
+    /// package, or as a LilyPond file of \fret-diagram markups, or as a MusicXML score of chord
+    /// symbols with fretboard frames, or as json VexChords charts, or as json in the structure of
+    /// chords-db, or as a csv table of voicings for spreadsheets, or as a Guitar Pro 5 file with a
+    /// bar for each voicing, or write svg chord diagrams or midi files to --out-dir
+    #[arg(long, value_enum, default_value = "json")]
+    format: Format,
+    /// Directory to write the svg diagrams or midi files to, one directory for each chord with a
+    /// file for each voicing, numbered best first
+    #[arg(long, required_if_eq_any([("format", "svg"), ("format", "midi")]))]
+    out_dir: Option<PathBuf>,
+    /// Generate every chord again instead of reading the ones generated before with the same
+    /// settings from the cache
+    #[arg(long)]
+    no_cache: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// What to make of the chords instead of printing them
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Typeset the chords as a PDF chord book to print, with a title page and an index
+    Book {
+        /// File to write the book to
+        #[arg(long, short)]
+        out: PathBuf,
+        /// Title printed on the title page
+        #[arg(long, default_value = "Chord Dictionary")]
+        title: String,
+        /// Chords printed on each page
+        #[arg(long, default_value_t = 6, value_parser = clap::value_parser!(u8).range(1..=12))]
+        chords_per_page: u8,
+        /// Diagrams printed of each chord, of its best voicings
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u8).range(1..=8))]
+        voicings: u8,
+        /// Order the chords by quality and then root, instead of by root
+        #[arg(long)]
+        by_quality: bool,
+    },
+    /// Write the chords into a database to query
+    Export {
+        #[command(subcommand)]
+        database: Database,
+    },
+    /// Print the JSON Schema of the json output, to validate it or generate types from
+    Schema,
+    /// Name the chords of a piece of music and print its chord sheet, with the best voicings of
+    /// each chord, 3 of them unless --limit says otherwise
+    Analyze {
+        #[command(subcommand)]
+        source: Source,
+    },
+    /// Play a voicing of a chord through the speakers
+    #[cfg(feature = "play")]
+    Play {
+        /// The chord to play, e.g. "Cmaj7"
+        symbol: String,
+        /// Which of its voicings to play, from 1 for the best
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u16).range(1..))]
+        voicing: u16,
+        /// How to play the strings: block, down, up or arpeggio
+        #[arg(long, default_value_t = chord_generator::Strum::Down)]
+        strum: chord_generator::Strum,
+    },
+    /// Print a song written in ChordPro with its chords over the lyrics, and a diagram of the
+    /// voicing to play each chord with
+    Lyrics {
+        /// The ChordPro file, with chords in brackets before the syllables they're played on
+        file: PathBuf,
+    },
+    /// Name the chords a fingering like x32010 or a set of notes like C E G Bb could be, the
+    /// likeliest first
+    Identify {
+        /// A fingering, the fret held on each string from the lowest with x for muted, separated
+        /// by - or spaces when a fret is 10 or higher. Or the notes, the lowest first
+        #[arg(required = true)]
+        played: Vec<String>,
+        /// Also name chords with tones left out or added, like a rootless C9 or a C with an added
+        /// F#, by how likely they are
+        #[arg(long)]
+        fuzzy: bool,
+    },
+    /// List the chords with all of some notes in them, like a melody or the open strings, in any
+    /// of the formats with the best voicings of each, 2 of them unless --limit says otherwise
+    Containing {
+        /// The notes, e.g. "E B" or "G A B"
+        #[arg(required_unless_present = "open_strings")]
+        notes: Vec<String>,
+        /// Also count the notes of the open strings, with the capo on
+        #[arg(long)]
+        open_strings: bool,
+    },
+    /// Search every chord for the voicings played like a pattern, e.g. "x 3-5 * * 0 x", and print
+    /// them, all of each chord's unless --limit says otherwise
+    Find {
+        /// The fret held on each string from the lowest, with x for muted, * for anything and
+        /// 3-5 for a fret from 3 to 5, separated by spaces
+        pattern: FretPattern,
+    },
+    /// Print how many voicings the chords have and what they're like: the base frets, spans,
+    /// barres and open strings, to see what the filters and scoring weights do
+    Stats,
+    /// List the chords without any voicings, with the filter that rejects the last of them
+    Coverage,
+    /// Check that well-known fingerings like the open chords and the F barre chord are generated
+    /// and ranked near the top, listing the ones that aren't
+    Verify,
+    /// Print an iReal Pro link to a chord chart, to open it in the app and practise along to it
+    Ireal {
+        /// The chart, bars of chords like "| Dm7 G7 | Cmaj7 | % |" with % for a bar played like
+        /// the one before and N.C. for no chord
+        chart: String,
+        /// Title of the song
+        #[arg(long, default_value = "Untitled")]
+        title: String,
+        /// Who wrote it, last name first the way iReal Pro sorts them
+        #[arg(long, default_value = "")]
+        composer: String,
+        /// The style iReal Pro plays it in, e.g. "Bossa Nova" or "Medium Swing"
+        #[arg(long, default_value = "Medium Swing")]
+        style: String,
+    },
+    /// Play random voicings and ask which chord each one is, starting with major and minor
+    /// chords and adding harder ones with each streak of right answers
+    #[cfg(feature = "play")]
+    Quiz {
+        /// Only ask for the quality, telling the root
+        #[arg(long)]
+        quality_only: bool,
+        /// How to play the strings: block, down, up or arpeggio
+        #[arg(long, default_value_t = chord_generator::Strum::Down)]
+        strum: chord_generator::Strum,
+    },
+    /// Listen to a MIDI keyboard and print the best voicings of each chord played on it, 3 of
+    /// them unless --limit says otherwise
+    #[cfg(feature = "listen")]
+    Listen {
+        /// Part of the name of the MIDI input port to listen on, the first port if not given
+        #[arg(long)]
+        port: Option<String>,
+    },
+}
+
+/// What music can be analyzed
+#[derive(Subcommand, Debug)]
+enum Source {
+    /// A Standard MIDI File, read a beat at a time
+    Midi {
+        /// The .mid file to read
+        file: PathBuf,
+    },
+}
+
+/// Databases the chords can be exported to
+#[derive(Subcommand, Debug)]
+enum Database {
+    /// An SQLite database with tables of chords, their voicings and the strings of each voicing
+    Sqlite {
+        /// File to write the database to, replacing it if it's there
+        #[arg(long, short)]
+        out: PathBuf,
+    },
+}
+
+fn parse_instrument(name: &str) -> Result<&'static Instrument, String> {
+    instrument(name).ok_or_else(|| {
+        let names: Vec<&str> = INSTRUMENTS.iter().map(|i| i.name).collect();
+        format!("unknown instrument, expected one of: {}", names.join(", "))
+    })
+}
+
+/// Parse a range like "5..9" or "5-9", with both ends at 1 or above.
+fn parse_range(s: &str) -> Option<RangeInclusive<u8>> {
+    let (lo, hi) = s.split_once("..").or_else(|| s.split_once('-'))?;
+    let lo: u8 = lo.trim().parse().ok()?;
+    let hi: u8 = hi.trim().parse().ok()?;
+    (lo > 0 && lo <= hi).then_some(lo..=hi)
+}
+
+fn parse_position(s: &str) -> Result<RangeInclusive<u8>, String> {
+    parse_range(s)
+        .ok_or_else(|| "expected a fret window like 5..9, starting at the 1st fret or above".into())
+}
+
+fn parse_strings(s: &str) -> Result<RangeInclusive<u8>, String> {
+    // a single string is fine too
+    match s.trim().parse::<u8>() {
+        Ok(n) if n > 0 => Ok(n..=n),
+        _ => parse_range(s).ok_or_else(|| "expected strings like 2-5, counting from 1".into()),
+    }
+}
+
+fn parse_reference_pitch(s: &str) -> Result<f32, String> {
+    match s.trim().parse::<f32>() {
+        Ok(hz) if hz > 0.0 && hz.is_finite() => Ok(hz),
+        _ => Err("expected a frequency in hertz, like 440".into()),
+    }
+}
+
+fn parse_scale_length(s: &str) -> Result<f32, String> {
+    match s.trim().parse::<f32>() {
+        Ok(mm) if mm > 0.0 && mm.is_finite() => Ok(mm),
+        _ => Err("expected a length in millimetres, like 648".into()),
+    }
+}
+
+/// An inversion to filter by, `None` for any. Not spelled as `Option` so clap doesn't treat the
+/// flag as optional.
+type AnyInversion = Option<Inversion>;
+
+fn parse_inversion(s: &str) -> Result<AnyInversion, String> {
+    if s.eq_ignore_ascii_case("any") {
+        return Ok(None);
+    }
+    s.parse().map(Some).map_err(|e| format!("{}", e))
+}
+
+fn parse_preset(name: &str) -> Result<&'static TuningPreset, String> {
+    tuning_preset(name).ok_or_else(|| {
+        let names: Vec<&str> = TUNING_PRESETS.iter().map(|p| p.name).collect();
+        format!(
+            "unknown tuning preset, expected one of: {}",
+            names.join(", ")
+        )
+    })
+}
+
+#[derive(Serialize)]
+struct TuningInfo {
+    /// Name of the preset, if the tuning came from one
+    preset: Option<&'static str>,
+    #[serde(serialize_with = "spelled_tuning")]
+    notes: Tuning,
+}
+
+/// Voicings of a single chord
+#[derive(Serialize)]
+struct ChordEntry {
+    /// Notes of the chord, spelled for its key
+    notes: Vec<&'static str>,
+    /// Notes the voicings may leave out
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    optional: Vec<&'static str>,
+    /// Bass note of a slash chord
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bass: Option<&'static str>,
+    /// What the filters were relaxed by to find the voicings, see --relax
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    relaxed: Vec<&'static str>,
+    voicings: Vec<Voicing>,
+}
+
+/// Chords on the same root, by quality and bass note for slash chords
+type Qualities = BTreeMap<(Chord, Option<Note>), ChordEntry>;
+
+/// The VexChords charts of the chords' voicings, keyed by chord symbol
+struct VexCharts<'a>(&'a Dictionary<'a>);
+
+impl Serialize for VexCharts<'_> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_map(self.0.by_root().flat_map(|(root, qualities)| {
+            qualities.into_iter().map(move |((chord, bass), entry)| {
+                let charts: Vec<VexChart> = entry.voicings.iter().map(VexChart::of).collect();
+                (ChordSymbol { root, chord, bass }.to_string(), charts)
+            })
+        }))
+    }
+}
+
+/// The chords in the JSON structure of the chords-db project
+#[derive(Serialize)]
+struct ChordsDb {
+    main: ChordsDbMain,
+    /// The tuning's notes by its name
+    tunings: BTreeMap<&'static str, Vec<&'static str>>,
+    keys: Vec<&'static str>,
+    suffixes: Vec<String>,
+    /// The chords of each key, by its name with sharps spelled out like "Csharp"
+    #[serde(serialize_with = "ordered_map")]
+    chords: Vec<(String, Vec<ChordsDbChord>)>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChordsDbMain {
+    strings: usize,
+    /// Frets a chart has to show for every position to fit
+    frets_on_chord: u8,
+    name: &'static str,
+    number_of_chords: usize,
+}
+
+#[derive(Serialize)]
+struct ChordsDbChord {
+    key: &'static str,
+    suffix: String,
+    positions: Vec<ChordsDbPosition>,
+}
+
+fn ordered_map<S: Serializer, K: Serialize, V: Serialize>(
+    entries: &[(K, V)],
+    s: S,
+) -> Result<S::Ok, S::Error> {
+    s.collect_map(entries.iter().map(|(k, v)| (k, v)))
+}
+
+/// Where a movable shape plays a chord
+#[derive(Serialize)]
+struct Position {
+    root: &'static str,
+    /// Fret the shape's first fret is moved to
+    fret: u8,
+}
+
+/// A movable shape with every chord it plays
+#[derive(Serialize)]
+struct ShapeEntry {
+    #[serde(flatten)]
+    shape: Shape,
+    positions: Vec<Position>,
+}
+
+/// Shapes by quality, and for slash chords by how many semitones the bass is above the root
+type Shapes = BTreeMap<(Chord, Option<u8>), Vec<ShapeEntry>>;
+
+/// How the chords are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Json,
+    Ascii,
+    Svg,
+    Html,
+    Latex,
+    Gtab,
+    Lilypond,
+    Musicxml,
+    Vexchords,
+    ChordsDb,
+    Csv,
+    Yaml,
+    Toml,
+    Msgpack,
+    Midi,
+    Gp5,
+}
+
+/// Generates the voicings of chord symbols
+struct Generator<'a> {
+    tuning: &'a [Pitch],
+    options: &'a Options,
+    /// Mirror the voicings for left-handed players
+    mirror: bool,
+    /// Relax the filters for chords without voicings
+    relax: bool,
+    cache: Option<Cache>,
+}
+
+impl Generator<'_> {
+    /// Voicings of a single chord
+    fn entry(&self, symbol: ChordSymbol) -> ChordEntry {
+        let ChordSymbol { root, chord, bass } = symbol;
+        let options = Options {
+            bass,
+            ..self.options.clone()
+        };
+        let generate = |options: &Options| match &self.cache {
+            Some(cache) => cache.generate_voicings(root, chord, self.tuning, options),
+            None => generate_voicings(root, chord, self.tuning, options),
+        };
+        let (mut voicings, relaxed) = if self.relax {
+            relax_until_found(&options, generate)
+        } else {
+            (generate(&options), Vec::new())
+        };
+        if self.mirror {
+            voicings.iter_mut().for_each(|v| v.mirror());
         }
+        let spelling = chord_spelling(root, chord);
+        let notes = spell_chord(root, chord);
+        let optional = chord
+            .tones()
+            .iter()
+            .zip(&notes)
+            .filter(|(t, _)| t.optional)
+            .map(|(_, &n)| n)
+            .collect();
+        ChordEntry {
+            notes,
+            optional,
+            bass: bass.map(|n| n.spelled(spelling)),
+            relaxed: relaxed.iter().map(Relaxation::name).collect(),
+            voicings,
+        }
+    }
+}
+
+/// Every chord, generated one root at a time as it's written out, so only one root's voicings
+/// are held in memory
+struct Dictionary<'a> {
+    /// Sorted by root, quality and bass note, without duplicates
+    symbols: Vec<ChordSymbol>,
+    generator: Generator<'a>,
+}
+
+impl Dictionary<'_> {
+    /// Each root's chords, generated in parallel
+    fn by_root(&self) -> impl Iterator<Item = (Note, Qualities)> + '_ {
+        self.symbols
+            .chunk_by(|a, b| a.root == b.root)
+            .map(|symbols| {
+                let qualities = symbols
+                    .par_iter()
+                    .map(|&s| ((s.chord, s.bass), self.generator.entry(s)))
+                    .collect();
+                (symbols[0].root, qualities)
+            })
+    }
+}
+
+/// Chords on the same root keyed by quality, slash chords by their quality and bass note, like
+/// "Major/F#".
+struct Entries(Qualities);
+
+impl Serialize for Entries {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_map(self.0.iter().map(|((chord, _), entry)| match entry.bass {
+            Some(bass) => (format!("{}/{}", chord.name(), bass), entry),
+            None => (chord.name().to_string(), entry),
+        }))
     }
 }
 
-impl Serialize for Finger {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
+impl Serialize for Dictionary<'_> {
+    /// Key the chords by root names like "Eb" instead of the enum variants, keeping them in
+    /// pitch order.
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_map(
+            self.by_root()
+                .map(|(root, qualities)| (root.spelled(key_spelling(root)), Entries(qualities))),
+        )
+    }
+}
+
+/// Either every voicing by chord, or the movable shapes by quality
+#[derive(Serialize)]
+enum Listing<'a> {
+    #[serde(rename = "chords")]
+    Chords(Dictionary<'a>),
+    #[serde(rename = "shapes", serialize_with = "named_shapes")]
+    Shapes(Shapes),
+}
+
+#[derive(Serialize)]
+struct Output<'a> {
+    instrument: &'static str,
+    tuning: TuningInfo,
+    capo: u8,
+    left_handed: bool,
+    #[serde(flatten)]
+    listing: Listing<'a>,
+}
+
+fn spelled_tuning<S: Serializer>(notes: &Tuning, s: S) -> Result<S::Ok, S::Error> {
+    let spelling = tuning_spelling(notes);
+    s.collect_seq(notes.iter().map(|p| p.spelled(spelling)))
+}
+
+/// Key the shapes by quality names, and slash chord shapes by quality and bass interval like
+/// "Major/4".
+fn named_shapes<S: Serializer>(shapes: &Shapes, s: S) -> Result<S::Ok, S::Error> {
+    s.collect_map(shapes.iter().map(|((chord, bass), entries)| match bass {
+        Some(semitones) => (format!("{}/{}", chord.name(), semitones), entries),
+        None => (chord.name().to_string(), entries),
+    }))
+}
+
+/// Group the voicings of each quality by their movable shape, in the order they first appear.
+fn movable_shapes(chords: &BTreeMap<Note, Qualities>, left_handed: bool) -> Shapes {
+    let mut shapes = Shapes::new();
+    for (&root, qualities) in chords {
+        for (&(chord, bass), entry) in qualities {
+            let interval = bass.and_then(|b| (0..12).find(|&i| root + i == b));
+            let entries = shapes.entry((chord, interval)).or_default();
+            for v in &entry.voicings {
+                let Some((mut shape, fret)) = Shape::of(v, root) else {
+                    continue;
+                };
+                if left_handed {
+                    shape.mirror();
+                }
+                let position = Position {
+                    root: root.spelled(key_spelling(root)),
+                    fret,
+                };
+                match entries.iter_mut().find(|e| e.shape == shape) {
+                    Some(e) => e.positions.push(position),
+                    None => entries.push(ShapeEntry {
+                        shape,
+                        positions: vec![position],
+                    }),
+                }
+            }
+        }
+    }
+    // up the neck from the nut
+    for entry in shapes.values_mut().flatten() {
+        entry.positions.sort_by_key(|p| p.fret);
+    }
+    shapes
+}
+
+/// Exit with a usage error for arguments that can only be checked after parsing them.
+fn invalid(error: impl std::fmt::Display) -> ! {
+    Cli::command()
+        .error(clap::error::ErrorKind::ValueValidation, error)
+        .exit()
+}
+
+/// The config file's settings, the defaults without one.
+fn load_config(path: Option<&PathBuf>) -> Config {
+    match path {
+        Some(path) => {
+            let s = std::fs::read_to_string(path)
+                .unwrap_or_else(|e| invalid(format!("can't read {}: {}", path.display(), e)));
+            Config::from_toml(&s).unwrap_or_else(|e| invalid(e))
+        }
+        None => Config::default(),
+    }
+}
+
+/// The JSON Schema of [`Output`].
+const SCHEMA: &str = include_str!("schema.json");
+
+fn main() {
+    let cli = Cli::parse();
+    if let Some(Command::Schema) = cli.command {
+        print!("{}", SCHEMA);
+        return;
+    }
+    let config = load_config(cli.config.as_ref());
+    // the built-in chord qualities and the ones defined in the config file
+    let custom = config.chords().unwrap_or_else(|e| invalid(e));
+    let chords: Vec<Chord> = Chord::builtins().chain(custom).collect();
+    if let Some(Command::Ireal {
+        chart,
+        title,
+        composer,
+        style,
+    }) = &cli.command
+    {
+        let sheet = ChordSheet::parse_in(chart, &chords).unwrap_or_else(|e| invalid(e));
+        println!("{}", ireal_url(&sheet, title, composer, style));
+        return;
+    }
+    let chord = cli
+        .chord
+        .as_ref()
+        .map(|s| parse_quality_in(s, &chords).unwrap_or_else(|e| invalid(e)));
+    let mut tuning = match (&cli.tuning, cli.tuning_preset) {
+        (Some(s), _) => {
+            let mut notes = parse_tuning(s).unwrap_or_else(|e| invalid(e));
+            if !has_octaves(s) {
+                place_near(&mut notes, cli.instrument.pitches()[0]);
+            }
+            TuningInfo {
+                preset: None,
+                notes,
+            }
+        }
+        (None, Some(preset)) => TuningInfo {
+            preset: Some(preset.name),
+            notes: preset.pitches(),
+        },
+        (None, None) => TuningInfo {
+            preset: None,
+            notes: cli.instrument.pitches(),
+        },
+    };
+    if let Some(Command::Identify { played, fuzzy }) = &cli.command {
+        let options = Options {
+            capo: cli.capo,
+            ..cli.instrument.options()
+        };
+        match identified(&played.join(" "), &tuning.notes, &options, cli.left_handed) {
+            Ok(Some((pitches, bass))) => finish(write_identified(&pitches, bass, &chords, *fuzzy)),
+            Ok(None) => println!("no notes are played"),
+            Err(e) => invalid(e),
+        }
+        return;
+    }
+    if let Some(string) = cli.root_string {
+        if usize::from(string) > tuning.notes.len() {
+            invalid(format!(
+                "there are only {} strings to play",
+                tuning.notes.len()
+            ));
+        }
+    }
+    if let Some(strings) = &cli.strings {
+        if *strings.end() as usize > tuning.notes.len() {
+            invalid(format!(
+                "there are only {} strings to play",
+                tuning.notes.len()
+            ));
+        }
+    }
+    let defaults = cli.instrument.options();
+    let min_strings = cli.min_strings.map_or(defaults.min_strings, usize::from);
+    let max_strings = cli.max_strings.map(usize::from).or(defaults.max_strings);
+    if min_strings > tuning.notes.len() {
+        invalid(format!(
+            "there are only {} strings to play",
+            tuning.notes.len()
+        ));
+    }
+    if max_strings.is_some_and(|max| max < min_strings) {
+        invalid("--max-strings can't be below --min-strings");
+    }
+    if cli.shapes && cli.command.is_some() {
+        invalid("--shapes can only be printed, not typeset or exported");
+    }
+    let data = matches!(
+        cli.format,
+        Format::Json | Format::Yaml | Format::Toml | Format::Msgpack
+    );
+    if cli.shapes && !data {
+        invalid("--shapes can only be printed as json, yaml, toml or msgpack");
+    }
+    // a book only prints the best few voicings of each chord
+    let limit = match &cli.command {
+        Some(Command::Book { voicings, .. }) => {
+            let voicings = usize::from(*voicings);
+            Some(cli.limit.map_or(voicings, |limit| limit.min(voicings)))
+        }
+        Some(Command::Analyze { .. }) => Some(cli.limit.unwrap_or(3)),
+        Some(Command::Containing { .. }) => Some(cli.limit.unwrap_or(2)),
+        // the chords are searched through all of their voicings
+        Some(Command::Find { .. }) | Some(Command::Verify) => None,
+        // the best voicing of each chord is the one to play
+        Some(Command::Lyrics { .. }) => Some(1),
+        #[cfg(feature = "play")]
+        Some(Command::Quiz { .. }) => Some(cli.limit.unwrap_or(QUIZ_VOICINGS)),
+        #[cfg(feature = "listen")]
+        Some(Command::Listen { .. }) => Some(cli.limit.unwrap_or(3)),
+        _ => cli.limit,
+    };
+    let options = Options {
+        // a window further up the neck than usual has to be searched too
+        max_fret: cli.max_fret.unwrap_or_else(|| {
+            let window_end = cli.position.as_ref().map_or(0, |p| *p.end());
+            defaults.max_fret.max(window_end)
+        }),
+        capo: cli.capo,
+        max_span: cli.max_span,
+        scale_length: cli.scale_length.unwrap_or(defaults.scale_length),
+        reference_pitch: cli.reference_pitch,
+        position: cli.position,
+        strings: cli.strings.clone(),
+        open_strings: match (cli.open_only, cli.no_open) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        },
+        min_strings,
+        max_strings,
+        root_in_bass: cli.root_in_bass || defaults.root_in_bass,
+        inversion: cli.inversion,
+        root_string: cli.root_string,
+        drop: cli.drop,
+        spacing: cli.spacing,
+        max_gap: cli.max_gap,
+        low_interval_limits: cli.low_interval_limits,
+        require_third: cli.require_third,
+        strict_doubling: cli.strict_doubling,
+        no_unisons: cli.no_unisons,
+        textures: cli.texture.clone(),
+        excluded_textures: cli.without_texture.clone(),
+        max_difficulty: cli.max_difficulty,
+        top: cli.top,
+        interior_mutes: cli.interior_mutes,
+        thumb: cli.thumb,
+        shell: cli.shell,
+        rootless: cli.rootless,
+        weights: config.weights,
+        sort: cli.sort,
+        limit,
+        diverse: cli.diverse,
+        ..defaults
+    };
+
+    let mut symbols: Vec<ChordSymbol> = if cli.symbols.is_empty() {
+        Note::iter()
+            .filter(|&r| cli.root.is_none_or(|x| x == r))
+            .cartesian_product(
+                chords
+                    .iter()
+                    .copied()
+                    .filter(|&c| chord.is_none_or(|x| x == c)),
+            )
+            .map(|(root, chord)| ChordSymbol {
+                root,
+                chord,
+                bass: None,
+            })
+            .collect()
+    } else {
+        cli.symbols
+            .iter()
+            .map(|s| ChordSymbol::parse_in(s, &chords).unwrap_or_else(|e| invalid(e)))
+            .collect()
+    };
+
+    if let Some(Command::Containing {
+        notes,
+        open_strings,
+    }) = &cli.command
+    {
+        let mut wanted: NoteSet = notes
+            .iter()
+            .flat_map(|n| n.split([' ', ',']))
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse::<Note>())
+            .collect::<Result<_, _>>()
+            .unwrap_or_else(|e| invalid(e));
+        if *open_strings {
+            wanted = wanted
+                | tuning
+                    .notes
+                    .iter()
+                    .map(|&p| (p + cli.capo).note())
+                    .collect();
+        }
+        let found = containing(wanted, &chords);
+        symbols.retain(|s| found.contains(&ChordSymbol { bass: None, ..*s }));
+        if symbols.is_empty() {
+            println!("no chord has all of these notes");
+            return;
+        }
+    }
+    if let Some(Command::Verify) = &cli.command {
+        if tuning.notes != REFERENCE_TUNING || cli.capo > 0 {
+            invalid("the reference fingerings are for guitar in standard tuning without a capo");
+        }
+        let references: Vec<ChordSymbol> = REFERENCES.iter().map(|r| r.chord()).collect();
+        symbols.retain(|s| references.contains(s));
+    }
+    // the output lists each chord once, by root and then quality
+    symbols.sort_by_key(|s| (s.root, s.chord, s.bass));
+    symbols.dedup();
+    // each root's chords are generated together, so they share the memory
+    let options = match cli.max_memory {
+        Some(mib) => {
+            let at_once = symbols
+                .chunk_by(|a, b| a.root == b.root)
+                .map(|chunk| chunk.len())
+                .max()
+                .unwrap_or(1);
+            let cap = memory_limit(mib.saturating_mul(1 << 20), tuning.notes.len(), at_once);
+            Options {
+                limit: Some(options.limit.map_or(cap, |limit| limit.min(cap))),
+                ..options
+            }
+        }
+        None => options,
+    };
+    let notes = tuning.notes.clone();
+    let dictionary = Dictionary {
+        symbols,
+        generator: Generator {
+            tuning: &notes,
+            options: &options,
+            // shapes are mirrored once they're found, as they number the strings, MusicXML, csv,
+            // Guitar Pro and databases number them themselves, and midi strums them from the
+            // lowest
+            mirror: cli.left_handed
+                && !cli.shapes
+                && !matches!(
+                    cli.format,
+                    Format::Musicxml | Format::Csv | Format::Midi | Format::Gp5
+                )
+                && !matches!(
+                    cli.command,
+                    Some(Command::Export { .. }) | Some(Command::Verify)
+                ),
+            relax: cli.relax,
+            cache: if cli.no_cache { None } else { Cache::user() },
+        },
+    };
+    if let Some(Command::Book {
+        out,
+        title,
+        chords_per_page,
+        voicings,
+        by_quality,
+    }) = &cli.command
+    {
+        let layout = BookLayout {
+            title: title.clone(),
+            subtitle: setup(cli.instrument, &tuning.notes, cli.capo, cli.left_handed),
+            chords_per_page: usize::from(*chords_per_page),
+            voicings_per_chord: usize::from(*voicings),
+        };
+        finish(write_book(out, &dictionary, &layout, *by_quality));
+        return;
+    }
+    #[cfg(feature = "play")]
+    if let Some(Command::Play {
+        symbol,
+        voicing,
+        strum,
+    }) = &cli.command
+    {
+        let symbol = ChordSymbol::parse_in(symbol, &chords).unwrap_or_else(|e| invalid(e));
+        let entry = dictionary.generator.entry(symbol);
+        let Some(v) = entry.voicings.get(usize::from(*voicing) - 1) else {
+            invalid(format!(
+                "{} only has {} voicings",
+                symbol,
+                entry.voicings.len()
+            ))
+        };
+        // drawn the way round the player holds it, but played from the lowest string
+        let mut played = v.clone();
+        if dictionary.generator.mirror {
+            played.mirror();
+        }
+        println!(
+            "{} ({})\n\n{}",
+            symbol,
+            entry.notes.join(" "),
+            ascii_diagram(v)
+        );
+        finish(play(&played, *strum));
+        return;
+    }
+    #[cfg(feature = "play")]
+    if let Some(Command::Quiz {
+        quality_only,
+        strum,
+    }) = &cli.command
+    {
+        finish(quiz(&dictionary, &chords, *quality_only, *strum));
+        return;
+    }
+    #[cfg(feature = "listen")]
+    if let Some(Command::Listen { port }) = &cli.command {
+        finish(listen(port.as_deref(), &dictionary, &chords));
+        return;
+    }
+    if let Some(Command::Analyze {
+        source: Source::Midi { file },
+    }) = &cli.command
+    {
+        let sheet = fs::read(file)
+            .map_err(|e| format!("couldn't read {}: {}", file.display(), e))
+            .and_then(|data| midi_chords(&data, &chords).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| invalid(e));
+        let mut out = BufWriter::new(io::stdout().lock());
+        finish(write_analysis(&mut out, &sheet, &dictionary).and_then(|()| out.flush()));
+        return;
+    }
+    if let Some(Command::Find { pattern }) = &cli.command {
+        if pattern.0.len() != tuning.notes.len() {
+            invalid(format!(
+                "the pattern has {} strings, the tuning has {}",
+                pattern.0.len(),
+                tuning.notes.len()
+            ));
+        }
+        let mut out = BufWriter::new(io::stdout().lock());
+        finish(write_found(&mut out, &dictionary, pattern, cli.limit).and_then(|()| out.flush()));
+        return;
+    }
+    if let Some(Command::Stats) = &cli.command {
+        let mut stats = Stats::default();
+        for (_, qualities) in dictionary.by_root() {
+            for (&(chord, _), entry) in &qualities {
+                stats.add(chord, &entry.voicings);
+            }
+        }
+        println!("{}", stats);
+        return;
+    }
+    if let Some(Command::Coverage) = &cli.command {
+        let mut out = BufWriter::new(io::stdout().lock());
+        finish(write_coverage(&mut out, &dictionary).and_then(|()| out.flush()));
+        return;
+    }
+    if let Some(Command::Verify) = &cli.command {
+        let mut out = BufWriter::new(io::stdout().lock());
+        let written = write_verification(&mut out, &dictionary);
+        match written.and_then(|failed| out.flush().map(|()| failed)) {
+            Ok(0) => {}
+            // exit with an error so scripts can tell something regressed
+            Ok(_) => std::process::exit(1),
+            Err(e) => finish(Err(e)),
+        }
+        return;
+    }
+    if let Some(Command::Lyrics { file }) = &cli.command {
+        let sheet = fs::read_to_string(file)
+            .map_err(|e| format!("couldn't read {}: {}", file.display(), e))
+            .and_then(|song| chords_over_lyrics(&song, &chords).map_err(|e| e.to_string()))
+            .unwrap_or_else(|e| invalid(e));
+        let mut out = BufWriter::new(io::stdout().lock());
+        finish(write_lyrics(&mut out, &sheet, &dictionary).and_then(|()| out.flush()));
+        return;
+    }
+    if let Some(Command::Export {
+        database: Database::Sqlite { out },
+    }) = &cli.command
     {
-        serializer.serialize_i8(<Finger as Into<i8>>::into(*self))
+        // a database left from before would have the chords twice
+        let removed = match fs::remove_file(out) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+        finish(removed.and_then(|()| export_sqlite(out, &dictionary).map_err(io::Error::other)));
+        return;
+    }
+    if cli.format == Format::Ascii {
+        let mut out = BufWriter::new(io::stdout().lock());
+        finish(write_diagrams(&mut out, &dictionary).and_then(|()| out.flush()));
+        return;
+    }
+    if let Format::Latex | Format::Gtab = cli.format {
+        let diagram = if cli.format == Format::Latex {
+            chordscheme
+        } else {
+            gtab
+        };
+        let mut out = BufWriter::new(io::stdout().lock());
+        finish(write_latex(&mut out, &dictionary, diagram).and_then(|()| out.flush()));
+        return;
+    }
+    if cli.format == Format::Lilypond {
+        let mut out = BufWriter::new(io::stdout().lock());
+        finish(write_lilypond(&mut out, &dictionary).and_then(|()| out.flush()));
+        return;
+    }
+    if cli.format == Format::Vexchords {
+        let mut out = BufWriter::new(io::stdout().lock());
+        let written = serde_json::to_writer_pretty(&mut out, &VexCharts(&dictionary))
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(out))
+            .and_then(|()| out.flush());
+        finish(written);
+        return;
+    }
+    if cli.format == Format::ChordsDb {
+        let tuning_name = match tuning.preset {
+            Some(name) => name,
+            None if cli.tuning.is_some() => "custom",
+            None => "standard",
+        };
+        let db = chords_db(&dictionary, cli.instrument, tuning_name, &tuning.notes);
+        let mut out = BufWriter::new(io::stdout().lock());
+        let written = serde_json::to_writer_pretty(&mut out, &db)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(out))
+            .and_then(|()| out.flush());
+        finish(written);
+        return;
+    }
+    if cli.format == Format::Csv {
+        let mut out = BufWriter::new(io::stdout().lock());
+        finish(write_csv(&mut out, &dictionary).and_then(|()| out.flush()));
+        return;
+    }
+    if cli.format == Format::Musicxml {
+        let mut out = BufWriter::new(io::stdout().lock());
+        finish(write_musicxml(&mut out, &dictionary, cli.instrument).and_then(|()| out.flush()));
+        return;
+    }
+    if cli.format == Format::Gp5 {
+        if tuning.notes.len() > GP5_STRINGS {
+            invalid(format!(
+                "Guitar Pro files can't have more than {} strings",
+                GP5_STRINGS
+            ));
+        }
+        let mut chords = Vec::new();
+        for (root, qualities) in dictionary.by_root() {
+            for ((chord, bass), entry) in qualities {
+                let symbol = ChordSymbol { root, chord, bass };
+                chords.extend(entry.voicings.into_iter().map(|v| (symbol, v)));
+            }
+        }
+        let title = format!(
+            "Chord dictionary: {}",
+            setup(cli.instrument, &tuning.notes, cli.capo, false)
+        );
+        let file = gp5_file(
+            &title,
+            cli.instrument.name,
+            &tuning.notes,
+            cli.capo,
+            &chords,
+        );
+        finish(io::stdout().lock().write_all(&file));
+        return;
+    }
+    if cli.format == Format::Html {
+        let title = format!(
+            "Chord dictionary: {}",
+            setup(cli.instrument, &tuning.notes, cli.capo, cli.left_handed)
+        );
+        let mut out = BufWriter::new(io::stdout().lock());
+        finish(write_html(&mut out, &dictionary, &title).and_then(|()| out.flush()));
+        return;
+    }
+    if let (Format::Svg | Format::Midi, Some(dir)) = (cli.format, &cli.out_dir) {
+        finish(write_files(dir, &dictionary, cli.format));
+        return;
+    }
+    if cli.left_handed {
+        tuning.notes.reverse();
+    }
+    let output = Output {
+        instrument: cli.instrument.name,
+        tuning,
+        capo: cli.capo,
+        left_handed: cli.left_handed,
+        listing: if cli.shapes {
+            let chords = dictionary.by_root().collect();
+            Listing::Shapes(movable_shapes(&chords, cli.left_handed))
+        } else {
+            Listing::Chords(dictionary)
+        },
+    };
+    // written as it's generated rather than all at once, except for toml which puts the tables
+    // after the plain values
+    let mut out = BufWriter::new(io::stdout().lock());
+    let written = match cli.format {
+        Format::Yaml => serde_yaml::to_writer(&mut out, &output).map_err(io::Error::other),
+        // with the field names, so it reads back like the json
+        Format::Msgpack => {
+            rmp_serde::encode::write_named(&mut out, &output).map_err(io::Error::other)
+        }
+        Format::Toml => serde_json::to_value(&output)
+            .map_err(io::Error::from)
+            .and_then(|value| toml::to_string(&toml_value(value)).map_err(io::Error::other))
+            .and_then(|s| out.write_all(s.as_bytes())),
+        _ => serde_json::to_writer_pretty(&mut out, &output)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(out)),
+    };
+    finish(written.and_then(|()| out.flush()));
+}
+
+/// `value` as toml, which has no null: null fields are left out, and null list entries like the
+/// pitch of a muted string take the place of a value of the same type as the others, -1 like a
+/// muted string's fret, an empty string for its note name or nan for its frequency.
+fn toml_value(value: serde_json::Value) -> toml::Value {
+    use serde_json::Value;
+    match value {
+        Value::Null => toml::Value::Integer(-1),
+        Value::Bool(b) => toml::Value::Boolean(b),
+        Value::Number(n) => n
+            .as_i64()
+            .map(toml::Value::Integer)
+            .unwrap_or_else(|| toml::Value::Float(n.as_f64().unwrap_or(f64::NAN))),
+        Value::String(s) => toml::Value::String(s),
+        Value::Array(values) => {
+            let missing = match values.iter().find(|v| !v.is_null()) {
+                Some(Value::String(_)) => toml::Value::String(String::new()),
+                Some(Value::Number(n)) if n.is_f64() => toml::Value::Float(f64::NAN),
+                _ => toml::Value::Integer(-1),
+            };
+            toml::Value::Array(
+                values
+                    .into_iter()
+                    .map(|v| match v {
+                        Value::Null => missing.clone(),
+                        v => toml_value(v),
+                    })
+                    .collect(),
+            )
+        }
+        Value::Object(fields) => toml::Value::Table(
+            fields
+                .into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, toml_value(v)))
+                .collect(),
+        ),
     }
 }
 
-type Fingering = [Finger; 6];
+/// Write each chord's name and notes followed by a diagram of each of its voicings.
+fn write_diagrams(out: &mut impl Write, dictionary: &Dictionary) -> io::Result<()> {
+    // each group is labelled when they're grouped by their root string
+    let grouped = dictionary.generator.options.sort == SortOrder::RootString;
+    for (root, qualities) in dictionary.by_root() {
+        for (&(chord, bass), entry) in &qualities {
+            let symbol = ChordSymbol { root, chord, bass };
+            write!(out, "{} ({})", symbol, entry.notes.join(" "))?;
+            if !entry.relaxed.is_empty() {
+                write!(out, ", relaxed to {}", entry.relaxed.join(", "))?;
+            }
+            writeln!(out)?;
+            for (i, v) in entry.voicings.iter().enumerate() {
+                if grouped && (i == 0 || entry.voicings[i - 1].root_string != v.root_string) {
+                    match v.root_string {
+                        Some(string) => writeln!(out, "\nroot on string {}", string)?,
+                        None => writeln!(out, "\nrootless")?,
+                    }
+                }
+                writeln!(out, "\n{}", ascii_diagram(v))?;
+            }
+            writeln!(out, "\n")?;
+        }
+    }
+    Ok(())
+}
+
+/// Play `v` with `strum` through the default audio output, returning once it's died away.
+#[cfg(feature = "play")]
+fn play(v: &Voicing, strum: chord_generator::Strum) -> io::Result<()> {
+    use chord_generator::{chord_samples, SAMPLE_RATE};
+    use std::num::{NonZeroU16, NonZeroU32};
+    let mut sink = rodio::DeviceSinkBuilder::open_default_sink().map_err(io::Error::other)?;
+    sink.log_on_drop(false);
+    let player = rodio::Player::connect_new(sink.mixer());
+    player.append(rodio::buffer::SamplesBuffer::new(
+        NonZeroU16::MIN,
+        NonZeroU32::new(SAMPLE_RATE).unwrap(),
+        chord_samples(v, strum, 3.0),
+    ));
+    player.sleep_until_end();
+    Ok(())
+}
+
+/// Print the chord sheet of a piece, then each of its chords in the order they're first played
+/// with their best voicings.
+fn write_analysis(
+    out: &mut impl Write,
+    sheet: &ChordSheet,
+    dictionary: &Dictionary,
+) -> io::Result<()> {
+    writeln!(out, "{}", sheet)?;
+    let mut seen = HashSet::new();
+    for symbol in sheet.changes.iter().filter_map(|c| c.symbol) {
+        if !seen.insert(symbol) {
+            continue;
+        }
+        let entry = dictionary.generator.entry(symbol);
+        writeln!(out, "\n{} ({})", symbol, entry.notes.join(" "))?;
+        for v in &entry.voicings {
+            writeln!(out, "\n{}", ascii_diagram(v))?;
+        }
+    }
+    Ok(())
+}
 
-fn next_fingering(fingering: &mut Fingering) -> bool {
-    for f in fingering.iter_mut().rev() {
-        match f.0 {
-            None => {
-                *f = Finger(Some(0));
-                return true;
+/// Print each chord with voicings played like `pattern`, with the first `limit` of them, or that
+/// none are.
+fn write_found(
+    out: &mut impl Write,
+    dictionary: &Dictionary,
+    pattern: &FretPattern,
+    limit: Option<usize>,
+) -> io::Result<()> {
+    let mut found = 0;
+    for (root, qualities) in dictionary.by_root() {
+        for (&(chord, bass), entry) in &qualities {
+            let matching: Vec<&Voicing> = entry
+                .voicings
+                .iter()
+                .filter(|v| pattern.matches(&v.frets))
+                .take(limit.unwrap_or(usize::MAX))
+                .collect();
+            if matching.is_empty() {
+                continue;
             }
-            Some(MAX_FRETS) => {
-                *f = Finger(None);
+            found += 1;
+            let symbol = ChordSymbol { root, chord, bass };
+            writeln!(out, "{} ({})", symbol, entry.notes.join(" "))?;
+            for v in matching {
+                writeln!(out, "\n{}", ascii_diagram(v))?;
+            }
+            writeln!(out, "\n")?;
+        }
+    }
+    if found == 0 {
+        writeln!(out, "no voicing is played like that")?;
+    }
+    Ok(())
+}
+
+/// Print each chord without voicings and the filter rejecting the last of them, or that every
+/// chord has some.
+fn write_coverage(out: &mut impl Write, dictionary: &Dictionary) -> io::Result<()> {
+    let generator = &dictionary.generator;
+    let mut empty = 0;
+    for (root, qualities) in dictionary.by_root() {
+        for (&(chord, bass), entry) in &qualities {
+            if !entry.voicings.is_empty() {
+                continue;
             }
-            Some(x) => {
-                *f = Finger(Some(x + 1));
-                return true;
+            empty += 1;
+            let symbol = ChordSymbol { root, chord, bass };
+            let options = Options {
+                bass,
+                ..generator.options.clone()
+            };
+            match eliminated_by(root, chord, generator.tuning, &options) {
+                Some(filter) => writeln!(
+                    out,
+                    "{}: no voicings, the last ones rejected by {}",
+                    symbol, filter
+                )?,
+                None => writeln!(
+                    out,
+                    "{}: no fingering up to fret {} plays it",
+                    symbol, options.max_fret
+                )?,
             }
         }
     }
-    // if we haven't returned by this point we have gone beyond the maximum possible fingerings, so
-    // return false to user
-    false
+    if empty == 0 {
+        writeln!(out, "all {} chords have voicings", dictionary.symbols.len())?;
+    }
+    Ok(())
 }
 
-fn compactness(fingering: &Fingering) -> i8 {
-    let played: Vec<i8> = fingering
-        .into_iter()
-        .filter_map(|&f| {
-            let x: i8 = f.into();
-            if x > 0 {
-                Some(x)
-            } else {
-                None
+/// Print each reference fingering of the chords that isn't generated, with the filter rejecting
+/// it, or is ranked lower than it should be, returning how many of them there are.
+fn write_verification(out: &mut impl Write, dictionary: &Dictionary) -> io::Result<usize> {
+    let generator = &dictionary.generator;
+    let mut failed = 0;
+    let mut checked = 0;
+    for (root, qualities) in dictionary.by_root() {
+        for reference in REFERENCES.iter().filter(|r| r.chord().root == root) {
+            let symbol = reference.chord();
+            let Some(entry) = qualities.get(&(symbol.chord, symbol.bass)) else {
+                continue;
+            };
+            checked += 1;
+            let Some(rank) = reference.rank(&entry.voicings) else {
+                failed += 1;
+                let options = Options {
+                    bass: symbol.bass,
+                    ..generator.options.clone()
+                };
+                let fingering = reference.fingering();
+                match rejected_by(root, symbol.chord, generator.tuning, &fingering, &options) {
+                    Some(filter) => writeln!(
+                        out,
+                        "{} {}: not generated, rejected by {}",
+                        symbol, reference.frets, filter
+                    )?,
+                    None => writeln!(
+                        out,
+                        "{} {}: not generated, even with every filter off",
+                        symbol, reference.frets
+                    )?,
+                }
+                continue;
+            };
+            if rank > reference.within {
+                failed += 1;
+                writeln!(
+                    out,
+                    "{} {}: ranked {} of {}, should be in the first {}",
+                    symbol,
+                    reference.frets,
+                    rank,
+                    entry.voicings.len(),
+                    reference.within
+                )?;
             }
-        })
-        .collect();
-    if played.len() == 0 {
-        return std::i8::MAX;
+        }
+    }
+    if failed == 0 {
+        writeln!(
+            out,
+            "all {} reference fingerings are generated and ranked near the top",
+            checked
+        )?;
+    }
+    Ok(failed)
+}
+
+/// The qualities a quiz asks for, a level at a time, the last one adding all the others.
+#[cfg(feature = "play")]
+const QUIZ_LEVELS: [&[Chord]; 3] = [
+    &[Chord::MAJOR, Chord::MINOR],
+    &[Chord::SEVENTH, Chord::MAJOR_SEVENTH, Chord::MINOR_SEVENTH],
+    &[
+        Chord::SUS2,
+        Chord::SUS4,
+        Chord::AUGMENTED,
+        Chord::DIMINISHED,
+    ],
+];
+/// Voicings of each chord a quiz plays from, the best ones on the first level and more after.
+#[cfg(feature = "play")]
+const QUIZ_VOICINGS: usize = 8;
+/// Right answers in a row to go up a level.
+#[cfg(feature = "play")]
+const LEVEL_UP: u32 = 3;
+/// Wrong answers in a row to go down one.
+#[cfg(feature = "play")]
+const LEVEL_DOWN: u32 = 2;
+
+/// Play random voicings of the dictionary's chords until the player quits, asking which chord
+/// each one is, or only its quality with `quality_only`. Streaks of right answers bring in
+/// harder qualities and voicings further down the list, and wrong ones take them out again.
+#[cfg(feature = "play")]
+fn quiz(
+    dictionary: &Dictionary,
+    chords: &[Chord],
+    quality_only: bool,
+    strum: chord_generator::Strum,
+) -> io::Result<()> {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let mut state = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(1, |d| d.as_nanos() as u64)
+        .max(1);
+    let mut random = |n: usize| {
+        // xorshift
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state % n as u64) as usize
+    };
+    let (mut level, mut streak, mut misses) = (0, 0, 0);
+    let (mut asked, mut right, mut best) = (0, 0, 0);
+    let mut lines = io::stdin().lines();
+    println!(
+        "Name each chord, like {}. Press enter to hear it again, ? to give up on it and q to \
+         stop.",
+        if quality_only { "m7" } else { "Am7" }
+    );
+    'rounds: loop {
+        let easy: Vec<Chord> = QUIZ_LEVELS
+            .iter()
+            .take(level + 1)
+            .flat_map(|l| l.iter().copied())
+            .collect();
+        let mut pool: Vec<ChordSymbol> = dictionary
+            .symbols
+            .iter()
+            .copied()
+            .filter(|s| level >= QUIZ_LEVELS.len() || easy.contains(&s.chord))
+            .collect();
+        if pool.is_empty() {
+            pool = dictionary.symbols.clone();
+        }
+        let symbol = pool[random(pool.len())];
+        let entry = dictionary.generator.entry(symbol);
+        if entry.voicings.is_empty() {
+            continue;
+        }
+        let v = &entry.voicings[random(entry.voicings.len().min(2 * level + 2))];
+        let mut played = v.clone();
+        if dictionary.generator.mirror {
+            played.mirror();
+        }
+        asked += 1;
+        if quality_only {
+            println!("\nChord {}, on {}:", asked, entry.notes[0]);
+        } else {
+            println!("\nChord {}:", asked);
+        }
+        let correct = loop {
+            play(&played, strum)?;
+            // None to hear it again
+            let answer = loop {
+                print!("> ");
+                io::stdout().flush()?;
+                let Some(line) = lines.next().transpose()? else {
+                    break 'rounds;
+                };
+                let parsed = match line.trim() {
+                    "" => break None,
+                    "?" => break Some(false),
+                    "q" => break 'rounds,
+                    answer if quality_only => {
+                        parse_quality_in(answer, chords).map(|c| c == symbol.chord)
+                    }
+                    answer => ChordSymbol::parse_in(answer, chords).map(|s| s == symbol),
+                };
+                match parsed {
+                    Ok(correct) => break Some(correct),
+                    Err(e) => println!("{}", e),
+                }
+            };
+            if let Some(correct) = answer {
+                break correct;
+            }
+        };
+        println!(
+            "{}, it's {} ({})\n\n{}",
+            if correct { "Right" } else { "No" },
+            symbol,
+            entry.notes.join(" "),
+            ascii_diagram(v)
+        );
+        if correct {
+            right += 1;
+            streak += 1;
+            misses = 0;
+            best = best.max(streak);
+            if streak % LEVEL_UP == 0 && level < QUIZ_LEVELS.len() {
+                level += 1;
+                println!(
+                    "{} in a row, on to level {} with harder chords",
+                    streak,
+                    level + 1
+                );
+            }
+        } else {
+            streak = 0;
+            misses += 1;
+            if misses == LEVEL_DOWN && level > 0 {
+                level -= 1;
+                misses = 0;
+                println!("Back to level {} with easier chords", level + 1);
+            }
+        }
+    }
+    println!("\n{} of {} right, {} in a row at best", right, asked, best);
+    Ok(())
+}
+
+/// The pitches `played` to `identify`, with the bass note: a fingering in tuning `t` with the
+/// capo and short strings of `options`, written the way round a left-handed player holds it if
+/// `left_handed`, or notes like "C E G", the lowest first. `None` for a fingering muting every
+/// string.
+fn identified(
+    played: &str,
+    t: &[Pitch],
+    options: &Options,
+    left_handed: bool,
+) -> Result<Option<(Vec<Pitch>, Note)>, String> {
+    let mut frets = match parse_fret_string(played) {
+        Ok(frets) => frets,
+        Err(e) => {
+            let notes: Vec<Note> = played
+                .split([' ', ','])
+                .filter(|n| !n.is_empty())
+                .map(|n| n.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|note_error: ParseError| {
+                    let letters = played
+                        .chars()
+                        .any(|c| c.is_ascii_alphabetic() && !c.eq_ignore_ascii_case(&'x'));
+                    if letters {
+                        note_error.to_string()
+                    } else {
+                        e.to_string()
+                    }
+                })?;
+            let Some(&bass) = notes.first() else {
+                return Err("no notes or frets given".to_string());
+            };
+            let pitches = notes.iter().map(|&n| Pitch::new(n, 4)).collect();
+            return Ok(Some((pitches, bass)));
+        }
+    };
+    if frets.len() != t.len() {
+        return Err(format!(
+            "{} has {} strings, the tuning has {}",
+            played,
+            frets.len(),
+            t.len()
+        ));
+    }
+    if left_handed {
+        frets.reverse();
+    }
+    // the frets are counted from the capo, like the generated ones
+    let (open, options) = apply_capo(t, options);
+    let starts = &options.string_starts;
+    for (i, f) in frets.iter().enumerate() {
+        let start = starts.get(i).copied().unwrap_or(0);
+        if let Some(fret) = f.0.filter(|&fret| fret > 0 && fret < start) {
+            return Err(format!(
+                "string {} begins at fret {}, it can't be fretted at {}",
+                frets.len() - i,
+                start,
+                fret
+            ));
+        }
     }
-    played.iter().max().unwrap() - played.iter().min().unwrap()
+    let pitches: Vec<Pitch> = get_played_pitches(&open, &frets, starts)
+        .into_iter()
+        .flatten()
+        .collect();
+    Ok(pitches
+        .iter()
+        .min()
+        .map(|p| p.note())
+        .map(|bass| (pitches, bass)))
 }
 
-// TODO: This is temporary, we need to instead assign actual fingers and have a cost function for
-// distance, cramping, crossing etc
-fn fingering_score(fingering: &Fingering) -> u32 {
-    let mut sum: u32 = 0;
-    // prefer compact chords
-    sum += (5 - compactness(fingering)) as u32;
-    for finger in fingering {
-        match finger.0 {
-            // Open strings are best, give em max points :)
-            Some(0) => sum += 15,
-            // Closed strings are okay but better to have them at the start of the neck
-            Some(x) => sum += (10 - x) as u32,
-            // Muting is better than playing
-            None => sum += 10,
+/// Print the notes of `pitches` with `bass` the lowest, then each chord they make with the notes
+/// of the chord and the other way it's spelled, the likeliest first. With `fuzzy`, chords they
+/// make with tones left out or added too, with how likely each is and the tones that differ.
+fn write_identified(
+    pitches: &[Pitch],
+    bass: Note,
+    chords: &[Chord],
+    fuzzy: bool,
+) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    let notes: NoteSet = pitches.iter().map(|p| p.note()).collect();
+    let found = if fuzzy {
+        identify_fuzzy(notes, bass, chords)
+    } else {
+        identify(notes, bass, chords)
+            .into_iter()
+            .map(|symbol| Candidate {
+                symbol,
+                confidence: 100,
+                missing: Vec::new(),
+                extra: Vec::new(),
+            })
+            .collect()
+    };
+    let names: Vec<&str> = match found.first().map(|c| c.symbol) {
+        Some(s) => {
+            let sounding: Vec<Option<Pitch>> = pitches.iter().copied().map(Some).collect();
+            spell_pitches(s.root, s.chord, &sounding)
+                .into_iter()
+                .flatten()
+                .collect()
+        }
+        None => {
+            let spelling = key_spelling(bass);
+            pitches.iter().map(|p| p.note().spelled(spelling)).collect()
         }
+    };
+    writeln!(out, "{}", names.join(" "))?;
+    if found.is_empty() {
+        writeln!(out, "no chord has just these notes")?;
     }
-    sum
+    for Candidate {
+        symbol,
+        confidence,
+        missing,
+        extra,
+    } in found
+    {
+        let spelled = spell_chord(symbol.root, symbol.chord);
+        write!(out, "{} ({})", symbol, spelled.join(" "))?;
+        if let Some(other) = symbol.respelled() {
+            write!(out, ", or {}", other)?;
+        }
+        if fuzzy {
+            write!(out, ": {}%", confidence)?;
+            // spelled like the chord's own notes
+            let tones = symbol.chord.notes(symbol.root);
+            let missing: Vec<&str> = missing
+                .iter()
+                .filter_map(|n| tones.iter().position(|t| t == n).map(|i| spelled[i]))
+                .collect();
+            if !missing.is_empty() {
+                write!(out, ", no {}", missing.join(" "))?;
+            }
+            let spelling = chord_spelling(symbol.root, symbol.chord);
+            let extra: Vec<&str> = extra.iter().map(|n| n.spelled(spelling)).collect();
+            if !extra.is_empty() {
+                write!(out, ", added {}", extra.join(" "))?;
+            }
+        }
+        writeln!(out)?;
+    }
+    Ok(())
 }
 
-fn get_played_notes(t: Tuning, fingering: Fingering) -> [Option<Note>; 6] {
-    let mut notes = [None; 6];
-    for (i, f) in fingering.into_iter().enumerate() {
-        notes[i] = t[i] + f;
+/// Diagrams printed side by side under the lyrics.
+const LEGEND_COLUMNS: usize = 5;
+
+/// Print the chords over the lyrics of a song, then a legend of the diagram of each chord's best
+/// voicing, a few to a row.
+fn write_lyrics(
+    out: &mut impl Write,
+    sheet: &LyricSheet,
+    dictionary: &Dictionary,
+) -> io::Result<()> {
+    write!(out, "{}", sheet.text)?;
+    let legend: Vec<Vec<String>> = sheet
+        .chords
+        .iter()
+        .map(|&symbol| {
+            let entry = dictionary.generator.entry(symbol);
+            let diagram = entry
+                .voicings
+                .first()
+                .map_or("no voicing".to_string(), ascii_diagram);
+            let mut lines = vec![symbol.to_string()];
+            lines.extend(diagram.lines().map(str::to_string));
+            lines
+        })
+        .collect();
+    for row in legend.chunks(LEGEND_COLUMNS) {
+        writeln!(out)?;
+        let widths: Vec<usize> = row
+            .iter()
+            .map(|lines| lines.iter().map(|l| l.chars().count()).max().unwrap_or(0))
+            .collect();
+        let height = row.iter().map(Vec::len).max().unwrap_or(0);
+        for i in 0..height {
+            let line: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(lines, &width)| {
+                    format!(
+                        "{:<width$}",
+                        lines.get(i).map_or("", String::as_str),
+                        width = width
+                    )
+                })
+                .collect();
+            writeln!(out, "{}", line.join("   ").trim_end())?;
+        }
     }
-    notes
+    Ok(())
 }
 
-fn gen_inversions(root: Note, chord: Chord, t: Tuning) -> Vec<Fingering> {
-    let mut inversions = Vec::new();
-    let mut fingering: Fingering = [Finger(None); 6];
+/// Print the best voicings of each chord played on the MIDI input port named like `port`, or the
+/// first one, as its keys are held down.
+#[cfg(feature = "listen")]
+fn listen(port: Option<&str>, dictionary: &Dictionary, chords: &[Chord]) -> io::Result<()> {
+    use chord_generator::{identify, NoteSet};
+    use std::{
+        sync::mpsc::{self, RecvTimeoutError},
+        time::Duration,
+    };
+    let input = midir::MidiInput::new("chord-generator").map_err(io::Error::other)?;
+    let port = input
+        .ports()
+        .into_iter()
+        .find(|p| port.is_none_or(|name| input.port_name(p).is_ok_and(|n| n.contains(name))))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such MIDI input port"))?;
+    let name = input.port_name(&port).map_err(io::Error::other)?;
+    let (sender, messages) = mpsc::channel();
+    let _connection = input
+        .connect(
+            &port,
+            "chord-generator",
+            move |_, message, _| {
+                let _ = sender.send(message.to_vec());
+            },
+            (),
+        )
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    eprintln!("listening on {}", name);
 
+    let mut held = BTreeSet::new();
+    let mut changed = false;
+    let mut last = None;
     loop {
-        let played_notes = get_played_notes(t, fingering);
-
-        // Check if all notes in this particular fingering are part of chord triad
-        let mut all_played_notes_valid = true;
-        for n in played_notes {
-            match n {
-                None => continue,
-                Some(note) => {
-                    if chord.notes(root).into_iter().find(|&x| x == note).is_none() {
-                        all_played_notes_valid = false;
-                        break;
+        // the keys of a chord never go down at quite the same time, so it's named once they
+        // settle
+        match messages.recv_timeout(Duration::from_millis(50)) {
+            Ok(message) => {
+                changed |= match *message {
+                    [status, key, velocity] if status & 0xF0 == 0x90 && velocity > 0 => {
+                        held.insert(Pitch(key))
                     }
+                    [status, key, _] if status & 0xF0 == 0x80 || status & 0xF0 == 0x90 => {
+                        held.remove(&Pitch(key))
+                    }
+                    _ => false,
                 }
             }
+            Err(RecvTimeoutError::Timeout) if changed => {
+                changed = false;
+                let Some(bass) = held.first() else {
+                    last = None;
+                    continue;
+                };
+                let notes: NoteSet = held.iter().map(|p| p.note()).collect();
+                let Some(&symbol) = identify(notes, bass.note(), chords).first() else {
+                    continue;
+                };
+                if last == Some(symbol) {
+                    continue;
+                }
+                last = Some(symbol);
+                let entry = dictionary.generator.entry(symbol);
+                let mut out = io::stdout().lock();
+                writeln!(out, "{} ({})", symbol, entry.notes.join(" "))?;
+                for v in &entry.voicings {
+                    writeln!(out, "\n{}", ascii_diagram(v))?;
+                }
+                writeln!(out, "\n")?;
+                out.flush()?;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
         }
+    }
+}
 
-        // Check if all notes of the chord are being held
-        let mut all_chord_notes_are_held = true;
-        for note in chord.notes(root) {
-            if played_notes
-                .into_iter()
-                .find(|&held| held == Some(note))
-                .is_none()
-            {
-                all_chord_notes_are_held = false;
+/// Write a directory of svg diagrams or midi files for each chord into `dir`, named after the
+/// chord symbol with `_` for the slash of slash chords.
+fn write_files(dir: &Path, dictionary: &Dictionary, format: Format) -> io::Result<()> {
+    for (root, qualities) in dictionary.by_root() {
+        for (&(chord, bass), entry) in &qualities {
+            let symbol = ChordSymbol { root, chord, bass }.to_string();
+            let chord_dir = dir.join(symbol.replace('/', "_"));
+            fs::create_dir_all(&chord_dir)?;
+            for (i, v) in entry.voicings.iter().enumerate() {
+                if format == Format::Midi {
+                    fs::write(
+                        chord_dir.join(format!("{}.mid", i + 1)),
+                        midi_file(v, &symbol),
+                    )?;
+                } else {
+                    fs::write(
+                        chord_dir.join(format!("{}.svg", i + 1)),
+                        svg_diagram(v, &symbol),
+                    )?;
+                }
             }
         }
+    }
+    Ok(())
+}
 
-        if all_played_notes_valid && all_chord_notes_are_held {
-            inversions.push(fingering);
+/// Write each chord's name and notes as a LaTeX comment followed by a `diagram` of each of its
+/// voicings.
+fn write_latex(
+    out: &mut impl Write,
+    dictionary: &Dictionary,
+    diagram: fn(&Voicing, &str) -> String,
+) -> io::Result<()> {
+    for (root, qualities) in dictionary.by_root() {
+        for (&(chord, bass), entry) in &qualities {
+            let symbol = ChordSymbol { root, chord, bass }.to_string();
+            writeln!(out, "% {} ({})", symbol, entry.notes.join(" "))?;
+            for v in &entry.voicings {
+                writeln!(out, "{}", diagram(v, &symbol))?;
+            }
+            writeln!(out)?;
         }
+    }
+    Ok(())
+}
 
-        if !next_fingering(&mut fingering) {
-            break;
+/// Write a LilyPond file with a line of fret diagrams of each chord's voicings, each named after
+/// the chord, below a comment of its name and notes.
+fn write_lilypond(out: &mut impl Write, dictionary: &Dictionary) -> io::Result<()> {
+    writeln!(out, "\\version \"2.24.0\"\n")?;
+    for (root, qualities) in dictionary.by_root() {
+        for (&(chord, bass), entry) in &qualities {
+            let symbol = ChordSymbol { root, chord, bass }.to_string();
+            writeln!(out, "% {} ({})", symbol, entry.notes.join(" "))?;
+            writeln!(out, "\\markup \\line {{")?;
+            for v in &entry.voicings {
+                let name = symbol.replace('\\', "\\\\").replace('"', "\\\"");
+                writeln!(
+                    out,
+                    "  \\center-column {{ \"{}\" {} }}",
+                    name,
+                    fret_diagram(v)
+                )?;
+            }
+            writeln!(out, "}}\n")?;
+        }
+    }
+    Ok(())
+}
+
+/// Write a csv table with a row for each voicing: its chord, the fret held on each string from
+/// the lowest, numbered from 1 for the highest like --strings, `x` for muted, then the fret its
+/// diagram starts at, its score and its difficulty.
+fn write_csv(out: &mut impl Write, dictionary: &Dictionary) -> io::Result<()> {
+    let strings = dictionary.generator.tuning.len();
+    let columns: Vec<String> = (1..=strings)
+        .rev()
+        .map(|s| format!("string_{}", s))
+        .collect();
+    writeln!(
+        out,
+        "symbol,root,quality,bass,{},base_fret,score,difficulty",
+        columns.join(",")
+    )?;
+    for (root, qualities) in dictionary.by_root() {
+        for (&(chord, bass), entry) in &qualities {
+            let symbol = ChordSymbol { root, chord, bass }.to_string();
+            for v in &entry.voicings {
+                let frets: Vec<String> = v
+                    .frets
+                    .iter()
+                    .map(|f| f.0.map_or("x".to_string(), |x| x.to_string()))
+                    .collect();
+                writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{}",
+                    csv_field(&symbol),
+                    root.spelled(chord_spelling(root, chord)),
+                    csv_field(chord.name()),
+                    entry.bass.unwrap_or(""),
+                    frets.join(","),
+                    first_diagram_fret(v),
+                    v.score.total,
+                    v.difficulty
+                )?;
+            }
         }
     }
-    inversions
+    Ok(())
 }
 
-// Is the fingering compact (true) or spread out across > 4 frets (false)
-fn is_compact(fingering: &Fingering) -> bool {
-    compactness(fingering) < 4
+/// `s` quoted for a csv field if it has commas or quotes in it.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
 }
 
-// Are the played strings contiguious (true) or have random unplayed strings in between (false)
-fn is_contiguous(fingering: &Fingering) -> bool {
-    let mut zone = 0;
-    // xx12xx is valid, where first xx are zone0, 12 are zone1, and last xx are zone3
-    for f in fingering {
-        if zone == 0 {
-            if f.0 != None {
-                zone = 1;
+/// The tables [`export_sqlite`] creates.
+const SQLITE_SCHEMA: &str = "
+CREATE TABLE chords (
+    id INTEGER PRIMARY KEY,
+    symbol TEXT NOT NULL UNIQUE,
+    root TEXT NOT NULL,
+    quality TEXT NOT NULL,
+    bass TEXT,
+    notes TEXT NOT NULL
+);
+CREATE TABLE voicings (
+    id INTEGER PRIMARY KEY,
+    chord_id INTEGER NOT NULL REFERENCES chords (id),
+    rank INTEGER NOT NULL,
+    base_fret INTEGER NOT NULL,
+    score INTEGER NOT NULL,
+    difficulty TEXT NOT NULL,
+    inversion TEXT,
+    drop_voicing TEXT,
+    rootless INTEGER NOT NULL,
+    barre_fret INTEGER
+);
+CREATE TABLE strings (
+    voicing_id INTEGER NOT NULL REFERENCES voicings (id),
+    string INTEGER NOT NULL,
+    fret INTEGER,
+    pitch INTEGER,
+    frequency REAL,
+    finger INTEGER,
+    PRIMARY KEY (voicing_id, string)
+);
+CREATE INDEX chords_root_quality ON chords (root, quality);
+CREATE INDEX voicings_chord ON voicings (chord_id, rank);
+CREATE INDEX voicings_difficulty ON voicings (difficulty, score);
+CREATE INDEX strings_fret ON strings (string, fret);
+";
+
+/// Write the chords into an empty SQLite database at `path`: a row in `chords` for each chord, in
+/// `voicings` for each of its voicings ranked from 1 for the best, and in `strings` for each
+/// string of a voicing, numbered from 1 for the highest like --strings, with a null fret and
+/// pitch when it's muted.
+fn export_sqlite(path: &Path, dictionary: &Dictionary) -> rusqlite::Result<()> {
+    let mut db = rusqlite::Connection::open(path)?;
+    let tx = db.transaction()?;
+    tx.execute_batch(SQLITE_SCHEMA)?;
+    {
+        let mut chords = tx.prepare(
+            "INSERT INTO chords (symbol, root, quality, bass, notes) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        let mut voicings = tx.prepare(
+            "INSERT INTO voicings (chord_id, rank, base_fret, score, difficulty, inversion, \
+             drop_voicing, rootless, barre_fret) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        )?;
+        let mut strings = tx.prepare(
+            "INSERT INTO strings (voicing_id, string, fret, pitch, frequency, finger) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        for (root, qualities) in dictionary.by_root() {
+            for (&(chord, bass), entry) in &qualities {
+                let chord_id = chords.insert((
+                    ChordSymbol { root, chord, bass }.to_string(),
+                    root.spelled(chord_spelling(root, chord)),
+                    chord.name(),
+                    entry.bass,
+                    entry.notes.join(" "),
+                ))?;
+                for (rank, v) in entry.voicings.iter().enumerate() {
+                    let voicing_id = voicings.insert((
+                        chord_id,
+                        rank as i64 + 1,
+                        first_diagram_fret(v),
+                        v.score.total,
+                        v.difficulty.name(),
+                        v.inversion.map(|i| i.name()),
+                        v.drop.map(|d| d.name()),
+                        v.rootless,
+                        v.barre.as_ref().map(|b| b.fret),
+                    ))?;
+                    let count = v.frets.len();
+                    for (i, (f, p)) in v.frets.iter().zip(&v.pitches).enumerate() {
+                        let finger = v.fingers.as_ref().and_then(|h| h[i]);
+                        let string = (count - i) as i64;
+                        let pitch = p.map(|p| p.0);
+                        strings.execute((
+                            voicing_id,
+                            string,
+                            f.0,
+                            pitch,
+                            v.frequencies[i],
+                            finger,
+                        ))?;
+                    }
+                }
             }
-            continue;
         }
-        if zone == 1 {
-            if f.0 == None {
-                zone = 2;
+    }
+    tx.commit()
+}
+
+/// Write a MusicXML score for `instrument` with a measure for each voicing, holding its chord
+/// symbol and fretboard frame over a rest.
+fn write_musicxml(
+    out: &mut impl Write,
+    dictionary: &Dictionary,
+    instrument: &Instrument,
+) -> io::Result<()> {
+    writeln!(
+        out,
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<!DOCTYPE score-partwise PUBLIC "-//Recordare//DTD MusicXML 4.0 Partwise//EN" "http://www.musicxml.org/dtds/partwise.dtd">
+<score-partwise version="4.0">
+<part-list>
+<score-part id="P1"><part-name>{}</part-name></score-part>
+</part-list>
+<part id="P1">"#,
+        instrument.name
+    )?;
+    let mut measures = 0;
+    for (root, qualities) in dictionary.by_root() {
+        for (&(chord, bass), entry) in &qualities {
+            let symbol = ChordSymbol { root, chord, bass };
+            for v in &entry.voicings {
+                measures += 1;
+                write_measure(out, measures, &harmony(symbol, v))?;
             }
-            continue;
         }
-        return false;
     }
-    true
+    // a score needs a measure even without any chords
+    if measures == 0 {
+        write_measure(out, 1, "")?;
+    }
+    writeln!(out, "</part>\n</score-partwise>")
 }
 
-// Make sure at least four strings are being played, three note chords sound too empty
-fn at_least_four_strings(fingering: &Fingering) -> bool {
-    fingering.into_iter().filter(|f| f.0.is_some()).count() >= 4
+/// Write measure `number` of a MusicXML score, a rest with `contents` before it, starting the
+/// score's attributes in the first one.
+fn write_measure(out: &mut impl Write, number: usize, contents: &str) -> io::Result<()> {
+    writeln!(out, r#"<measure number="{}">"#, number)?;
+    if number == 1 {
+        writeln!(
+            out,
+            "<attributes><divisions>1</divisions><key><fifths>0</fifths></key>\
+             <time><beats>4</beats><beat-type>4</beat-type></time>\
+             <clef><sign>G</sign><line>2</line><clef-octave-change>-1</clef-octave-change></clef>\
+             </attributes>"
+        )?;
+    }
+    writeln!(out, "{}", contents)?;
+    writeln!(
+        out,
+        r#"<note><rest measure="yes"/><duration>4</duration></note>
+</measure>"#
+    )
 }
 
-/*
-Chords that can be played with 4 fingers easily with first finger optionally barring over a fret
-xx4532 is good
-224432 is good (barre with first finger)
-654776 is not good
-*/
-fn is_four_fingered(fingering: &Fingering) -> bool {
-    // find min finger that's not open/muted string
-    let played: Vec<i8> = fingering
-        .into_iter()
-        .filter_map(|&f| {
-            let x: i8 = f.into();
-            if x > 0 {
-                Some(x)
-            } else {
-                None
+/// The page [`write_html`] writes, up to the menus of roots and qualities.
+const HTML_HEAD: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body { font-family: sans-serif; margin: 2em; }
+details.root > summary { font-size: 1.5em; font-weight: bold; cursor: pointer; margin: 0.5em 0; }
+section.chord h2 { font-size: 1.1em; margin: 1em 0 0.25em; }
+section.chord h2 small { font-weight: normal; color: #555; }
+section.chord svg { margin-right: 0.5em; }
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>
+<label>Root <select id="root"><option value="">All</option>"#;
+
+/// What [`write_html`] writes after the chords, hiding the ones not picked in the menus.
+const HTML_TAIL: &str = r#"<script>
+const root = document.getElementById("root");
+const quality = document.getElementById("quality");
+function filter() {
+  for (const section of document.querySelectorAll("details.root")) {
+    let shown = 0;
+    for (const chord of section.querySelectorAll("section.chord")) {
+      const show = (!root.value || section.dataset.root === root.value)
+        && (!quality.value || chord.dataset.quality === quality.value);
+      chord.hidden = !show;
+      shown += show;
+    }
+    section.hidden = !shown;
+  }
+}
+root.onchange = quality.onchange = filter;
+</script>
+</body>
+</html>"#;
+
+/// Write a single html page of the chords with svg diagrams of their voicings, in a collapsible
+/// section for each root, with menus to only show some roots or qualities. It needs nothing else
+/// to be read offline.
+fn write_html(out: &mut impl Write, dictionary: &Dictionary, title: &str) -> io::Result<()> {
+    let root_name = |root: Note| root.spelled(key_spelling(root));
+    writeln!(out, "{}", HTML_HEAD.replace("{title}", &escape_html(title)))?;
+    for root in dictionary.symbols.iter().map(|s| s.root).dedup() {
+        writeln!(out, "<option>{}</option>", root_name(root))?;
+    }
+    writeln!(out, "</select></label>")?;
+    writeln!(
+        out,
+        r#"<label>Quality <select id="quality"><option value="">All</option>"#
+    )?;
+    let qualities: BTreeSet<Chord> = dictionary.symbols.iter().map(|s| s.chord).collect();
+    for chord in qualities {
+        writeln!(out, "<option>{}</option>", escape_html(chord.name()))?;
+    }
+    writeln!(out, "</select></label>\n</p>")?;
+
+    for (root, qualities) in dictionary.by_root() {
+        writeln!(
+            out,
+            r#"<details class="root" data-root="{0}" open>
+<summary>{0}</summary>"#,
+            root_name(root)
+        )?;
+        for (&(chord, bass), entry) in &qualities {
+            let symbol = ChordSymbol { root, chord, bass }.to_string();
+            writeln!(
+                out,
+                r#"<section class="chord" data-quality="{}">
+<h2>{} <small>{}</small></h2>"#,
+                escape_html(chord.name()),
+                escape_html(&symbol),
+                entry.notes.join(" ")
+            )?;
+            for v in &entry.voicings {
+                write!(out, "{}", svg_diagram(v, &symbol))?;
             }
+            writeln!(out, "</section>")?;
+        }
+        writeln!(out, "</details>")?;
+    }
+    writeln!(out, "{}", HTML_TAIL)
+}
+
+/// Escape the characters html treats specially in text and attribute values.
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The instrument, tuning and capo the chords are for, like "guitar, tuned E2 A2 D3 G3 B3 E4".
+fn setup(instrument: &Instrument, tuning: &Tuning, capo: u8, left_handed: bool) -> String {
+    let spelling = tuning_spelling(tuning);
+    let notes: Vec<String> = tuning.iter().map(|p| p.spelled(spelling)).collect();
+    let mut setup = format!("{}, tuned {}", instrument.name, notes.join(" "));
+    if capo > 0 {
+        setup += &format!(", capo at fret {}", capo);
+    }
+    if left_handed {
+        setup += ", left-handed";
+    }
+    setup
+}
+
+/// The chords-db document of the chords, for `instrument` tuned to `tuning` named `tuning_name`.
+fn chords_db(
+    dictionary: &Dictionary,
+    instrument: &Instrument,
+    tuning_name: &'static str,
+    tuning: &Tuning,
+) -> ChordsDb {
+    let mut keys = Vec::new();
+    let mut suffixes = Vec::new();
+    let mut chords = Vec::new();
+    for (root, qualities) in dictionary.by_root() {
+        let key = chords_db_key(root);
+        keys.push(key);
+        let entries: Vec<ChordsDbChord> = qualities
+            .into_iter()
+            .map(|((chord, _), entry)| {
+                let suffix = chords_db_suffix(chord, entry.bass);
+                suffixes.push((chord, suffix.clone()));
+                ChordsDbChord {
+                    key,
+                    suffix,
+                    positions: entry.voicings.iter().map(ChordsDbPosition::of).collect(),
+                }
+            })
+            .collect();
+        chords.push((key.replace('#', "sharp"), entries));
+    }
+    suffixes.sort();
+    suffixes.dedup();
+    let spelling = tuning_spelling(tuning);
+    let frets_on_chord = chords
+        .iter()
+        .flat_map(|(_, entries)| entries)
+        .flat_map(|c| &c.positions)
+        .flat_map(|p| p.frets.iter().copied())
+        .max()
+        .map_or(4, |top| top.max(4) as u8);
+    ChordsDb {
+        main: ChordsDbMain {
+            strings: tuning.len(),
+            frets_on_chord,
+            name: instrument.name,
+            number_of_chords: dictionary.symbols.len(),
+        },
+        tunings: BTreeMap::from([(
+            tuning_name,
+            tuning.iter().map(|p| p.note().spelled(spelling)).collect(),
+        )]),
+        keys,
+        suffixes: suffixes.into_iter().map(|(_, suffix)| suffix).collect(),
+        chords,
+    }
+}
+
+/// Write a PDF chord book of the chords to `path`, ordered by quality first if `by_quality`.
+fn write_book(
+    path: &Path,
+    dictionary: &Dictionary,
+    layout: &BookLayout,
+    by_quality: bool,
+) -> io::Result<()> {
+    let mut entries: Vec<(ChordSymbol, ChordEntry)> = dictionary
+        .by_root()
+        .flat_map(|(root, qualities)| {
+            qualities
+                .into_iter()
+                .map(move |((chord, bass), entry)| (ChordSymbol { root, chord, bass }, entry))
+        })
+        .collect();
+    if by_quality {
+        entries.sort_by_key(|(s, _)| (s.chord, s.root, s.bass));
+    }
+    let chords: Vec<BookChord> = entries
+        .iter()
+        .map(|(symbol, entry)| BookChord {
+            name: symbol.to_string(),
+            notes: entry.notes.iter().map(|n| n.to_string()).collect(),
+            voicings: &entry.voicings,
         })
         .collect();
-    let min = played.iter().min().unwrap();
-    // count played notes that are not the min (smallest fret - barred)
-    played.iter().filter(|&x| x != min).count() < 4
+    fs::write(path, chord_book(&chords, layout))
 }
-fn main() {
-    let mut m: BTreeMap<Note, BTreeMap<Chord, Vec<Fingering>>> = BTreeMap::new();
 
-    for root in Note::iter() {
-        m.insert(root, BTreeMap::new());
-        for chord in Chord::iter() {
-            let inversions: Vec<Fingering> = gen_inversions(root, chord, DEFAULT_TUNING)
-                .into_iter()
-                .filter(is_compact) // only compact
-                .filter(is_contiguous) // only contiguous
-                .filter(at_least_four_strings) // at least four played strings
-                .filter(is_four_fingered) // only what can be held easily with four fingers
-                .sorted_by(|a, b| {
-                    // sort the fingerings by descending score
-                    u32::cmp(&fingering_score(b), &fingering_score(a))
-                })
-                .collect();
-            // insert list of inversions for this particular chord
-            m.get_mut(&root).unwrap().insert(chord, inversions.clone());
+/// Exit with an error if the output couldn't be written.
+fn finish(written: io::Result<()>) {
+    match written {
+        Ok(()) => {}
+        // stop quietly when piped into something like head
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chord_generator::{fret_string, DEFAULT_TUNING};
+
+    fn identify_guitar(played: &str) -> Result<Option<(Vec<Pitch>, Note)>, String> {
+        identified(played, DEFAULT_TUNING, &Options::default(), false)
+    }
+
+    #[test]
+    fn identifies_notes_and_fingerings() {
+        let (pitches, bass) = identify_guitar("G B D").unwrap().unwrap();
+        assert_eq!(bass, Note::G);
+        assert_eq!(pitches.len(), 3);
+        let (pitches, bass) = identify_guitar("x32010").unwrap().unwrap();
+        assert_eq!(bass, Note::C);
+        assert_eq!(pitches.len(), 5);
+        assert_eq!(identify_guitar("xxxxxx").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_nothing_given() {
+        assert_eq!(identify_guitar("").unwrap_err(), "no notes or frets given");
+        assert_eq!(
+            identify_guitar("  ").unwrap_err(),
+            "no notes or frets given"
+        );
+        assert_eq!(
+            identify_guitar(" , ").unwrap_err(),
+            "no notes or frets given"
+        );
+    }
+
+    #[test]
+    fn toml_keeps_list_types() {
+        let json = serde_json::json!({
+            "bass": null,
+            "frets": [null, 3, 2],
+            "notes": [null, "C", "E"],
+            "frequencies": [null, 130.81, 164.81],
+        });
+        let toml = toml_value(json);
+        assert!(toml.get("bass").is_none());
+        assert_eq!(toml["frets"][0], toml::Value::Integer(-1));
+        assert_eq!(toml["notes"][0], toml::Value::String(String::new()));
+        assert!(toml["frequencies"][0].as_float().is_some_and(f64::is_nan));
+    }
+
+    #[test]
+    fn identifies_capoed_banjo_voicings() {
+        let banjo = instrument("banjo").unwrap();
+        let t = banjo.pitches();
+        let options = Options {
+            capo: 2,
+            ..banjo.options()
+        };
+        let voicings = generate_voicings(Note::A, Chord::MAJOR, &t, &options);
+        // the short string begins at fret 3 above the capo
+        assert!(voicings.iter().any(|v| v.frets[0].0.is_some_and(|f| f > 3)));
+        for v in voicings {
+            let played = fret_string(&v.frets);
+            let (pitches, _) = identified(&played, &t, &options, false).unwrap().unwrap();
+            let sounding: Vec<Pitch> = v.pitches.iter().flatten().copied().collect();
+            assert_eq!(pitches, sounding, "{}", played);
         }
+        assert!(identified("2 0 0 0 0", &t, &options, false).is_err());
     }
-    println!("{}", serde_json::to_string_pretty(&m).unwrap());
 }
@@ -0,0 +1,70 @@
+//! Writing voicings as Standard MIDI Files, to listen to them in any player.
+
+use crate::Voicing;
+
+/// Ticks per quarter note.
+const TICKS: u16 = 480;
+/// A bar of four beats.
+const BAR: u32 = 4 * TICKS as u32;
+/// Between the strings of a slow strum, an eighth of a beat.
+const STRUM: u32 = TICKS as u32 / 8;
+/// The block chord stops a sixteenth before the strum, so it's heard again.
+const GAP: u32 = TICKS as u32 / 4;
+/// General MIDI's steel-string acoustic guitar, numbered from 1.
+const GUITAR: u8 = 25;
+const VELOCITY: u8 = 80;
+
+/// A MIDI file of `v` named `name`, a bar of the chord played as a block and then a bar of it
+/// strummed slowly down from the lowest string, at 120 beats a minute. The strings are strummed
+/// in the order of `v.pitches`, so `v` mustn't be mirrored for left-handed players.
+pub fn midi_file(v: &Voicing, name: &str) -> Vec<u8> {
+    let pitches: Vec<u8> = v.pitches.iter().flatten().map(|p| p.0).collect();
+    // (tick, event), sorted so note offs come before note ons at the same tick
+    let mut events: Vec<(u32, Vec<u8>)> = Vec::new();
+    for (i, &pitch) in pitches.iter().enumerate() {
+        events.push((BAR - GAP, vec![0x80, pitch, 0]));
+        events.push((2 * BAR, vec![0x80, pitch, 0]));
+        events.push((0, vec![0x90, pitch, VELOCITY]));
+        events.push((BAR + i as u32 * STRUM, vec![0x90, pitch, VELOCITY]));
+    }
+    events.sort_by_key(|(tick, event)| (*tick, event[0] == 0x90));
+
+    let mut track = Vec::new();
+    // 500000 microseconds a beat
+    track.extend([0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20]);
+    track.extend([0x00, 0xFF, 0x03]);
+    write_length(&mut track, name.len() as u32);
+    track.extend(name.as_bytes());
+    track.extend([0x00, 0xC0, GUITAR - 1]);
+    let mut now = 0;
+    for (tick, event) in events {
+        write_length(&mut track, tick - now);
+        track.extend(event);
+        now = tick;
+    }
+    write_length(&mut track, 2 * BAR - now);
+    track.extend([0xFF, 0x2F, 0x00]);
+
+    // a single track file
+    let mut file = b"MThd".to_vec();
+    file.extend(6u32.to_be_bytes());
+    file.extend(0u16.to_be_bytes());
+    file.extend(1u16.to_be_bytes());
+    file.extend(TICKS.to_be_bytes());
+    file.extend(b"MTrk");
+    file.extend((track.len() as u32).to_be_bytes());
+    file.extend(track);
+    file
+}
+
+/// Append `n` as a variable-length quantity, seven bits a byte with the high bit set on all but
+/// the last.
+fn write_length(out: &mut Vec<u8>, n: u32) {
+    let mut bytes = vec![(n & 0x7F) as u8];
+    let mut rest = n >> 7;
+    while rest > 0 {
+        bytes.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    out.extend(bytes.iter().rev());
+}
@@ -0,0 +1,121 @@
+//! Writing voicings as MusicXML chord symbols with fretboard frames.
+
+use crate::diagram::{barre_ends, diagram_frets, finger_label};
+use crate::{chord_spelling, Chord, ChordSymbol, Note, Spelling, Voicing};
+
+/// A MusicXML `<harmony>` element of `symbol` played as `v`: the chord's root, kind and bass
+/// note, then a `<frame>` of the fretboard. Strings are numbered from 1 for the highest, as
+/// MusicXML does, so `v` mustn't be mirrored for left-handed players.
+pub fn harmony(symbol: ChordSymbol, v: &Voicing) -> String {
+    let spelling = chord_spelling(symbol.root, symbol.chord);
+    let (kind, degrees) = kind(symbol.chord);
+    let mut xml = vec!["<harmony>".to_string()];
+    xml.push(format!(
+        "  <root>{}</root>",
+        pitch("root", symbol.root, spelling)
+    ));
+    xml.push(format!(
+        r#"  <kind text="{}">{}</kind>"#,
+        escape(symbol.chord.symbol()),
+        kind
+    ));
+    for &(value, alter, kind) in degrees {
+        xml.push(format!(
+            "  <degree><degree-value>{}</degree-value><degree-alter>{}</degree-alter><degree-type>{}</degree-type></degree>",
+            value, alter, kind
+        ));
+    }
+    if let Some(bass) = symbol.bass {
+        xml.push(format!("  <bass>{}</bass>", pitch("bass", bass, spelling)));
+    }
+
+    let frets = diagram_frets(v);
+    let strings = v.frets.len();
+    xml.push("  <frame>".to_string());
+    xml.push(format!("    <frame-strings>{}</frame-strings>", strings));
+    xml.push(format!("    <frame-frets>{}</frame-frets>", frets.len()));
+    if *frets.start() > 1 {
+        xml.push(format!("    <first-fret>{}</first-fret>", frets.start()));
+    }
+    let barre = v.barre.as_ref().and_then(|b| barre_ends(v, b.fret));
+    // muted strings are left out
+    for (i, f) in v.frets.iter().enumerate() {
+        let Some(fret) = f.0 else { continue };
+        xml.push("    <frame-note>".to_string());
+        xml.push(format!("      <string>{}</string>", strings - i));
+        xml.push(format!("      <fret>{}</fret>", fret));
+        if let Some(finger) = v.fingers.as_ref().and_then(|h| h[i]) {
+            xml.push(format!(
+                "      <fingering>{}</fingering>",
+                finger_label(finger)
+            ));
+        }
+        match barre {
+            Some((first, _)) if first == i => xml.push(r#"      <barre type="start"/>"#.into()),
+            Some((_, last)) if last == i => xml.push(r#"      <barre type="stop"/>"#.into()),
+            _ => {}
+        }
+        xml.push("    </frame-note>".to_string());
+    }
+    xml.push("  </frame>".to_string());
+    xml.push("</harmony>".to_string());
+    xml.join("\n")
+}
+
+/// The MusicXML kind of `chord`, and the degrees added to or altered in it that the kind doesn't
+/// name, as their value, alteration and type. Custom qualities are `other`.
+fn kind(chord: Chord) -> (&'static str, &'static [(u8, i8, &'static str)]) {
+    match chord.name() {
+        "Major" => ("major", &[]),
+        "Minor" => ("minor", &[]),
+        "Augmented" => ("augmented", &[]),
+        "Diminished" => ("diminished", &[]),
+        "Seventh" => ("dominant", &[]),
+        "MajorSeventh" => ("major-seventh", &[]),
+        "MinorSeventh" => ("minor-seventh", &[]),
+        "Sus2" => ("suspended-second", &[]),
+        "Sus4" => ("suspended-fourth", &[]),
+        "MinorMajorSeventh" => ("major-minor", &[]),
+        "DiminishedSeventh" => ("diminished-seventh", &[]),
+        "MajorNinth" => ("major-ninth", &[]),
+        "MinorNinth" => ("minor-ninth", &[]),
+        "AddNinth" => ("major", &[(9, 0, "add")]),
+        "AddEleventh" => ("major", &[(11, 0, "add")]),
+        "MinorSixth" => ("minor-sixth", &[]),
+        "MajorSixth" => ("major-sixth", &[]),
+        "AddSixthAddNinth" => ("major-sixth", &[(9, 0, "add")]),
+        "Five" => ("power", &[]),
+        "SeventhFlatNinth" => ("dominant", &[(9, -1, "add")]),
+        "SeventhSharpNinth" => ("dominant", &[(9, 1, "add")]),
+        "SeventhFlatFifth" => ("dominant", &[(5, -1, "alter")]),
+        "SeventhSharpFifth" => ("augmented-seventh", &[]),
+        "HalfDiminished" => ("half-diminished", &[]),
+        "Ninth" => ("dominant-ninth", &[]),
+        "Eleventh" => ("dominant-11th", &[]),
+        "Thirteenth" => ("dominant-13th", &[]),
+        _ => ("other", &[]),
+    }
+}
+
+/// The step and alteration elements of `note` spelled with `spelling`, named like
+/// `<root-step>` and `<root-alter>` for `part` "root".
+fn pitch(part: &str, note: Note, spelling: Spelling) -> String {
+    let name = note.spelled(spelling);
+    let alter: i32 = name[1..]
+        .chars()
+        .map(|c| if c == '#' { 1 } else { -1 })
+        .sum();
+    let mut xml = format!("<{part}-step>{}</{part}-step>", &name[..1]);
+    if alter != 0 {
+        xml += &format!("<{part}-alter>{alter}</{part}-alter>");
+    }
+    xml
+}
+
+/// Escape the characters XML treats specially in attribute values.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,26 @@
+//! Physical distances along the neck, as frets get closer together further up.
+
+use crate::Finger;
+use itertools::Itertools;
+
+/// Distance from the nut to `fret` on a neck of `scale` length, in the same unit.
+pub fn fret_position(scale: f32, fret: u8) -> f32 {
+    scale * (1.0 - 2f32.powf(-(fret as f32) / 12.0))
+}
+
+/// How far the fingers stretch along the neck to hold `fingering`, from the fret wire below its
+/// lowest fretted note to the one above its highest, in the unit of `scale`. 0 when nothing is
+/// fretted.
+pub fn reach(fingering: &[Finger], scale: f32) -> f32 {
+    let fretted = fingering.iter().filter_map(|f| f.0).filter(|&x| x > 0);
+    match fretted.minmax().into_option() {
+        Some((lo, hi)) => fret_position(scale, hi) - fret_position(scale, lo - 1),
+        None => 0.0,
+    }
+}
+
+/// How many frets from the nut up a `reach` covers, so a stretch anywhere on the neck can be
+/// compared with what it takes in first position.
+pub fn first_position_frets(reach: f32, scale: f32) -> f32 {
+    -12.0 * (1.0 - reach / scale).log2()
+}
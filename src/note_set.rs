@@ -0,0 +1,74 @@
+//! Sets of notes as pitch-class bitmasks.
+
+use crate::Note;
+use std::ops::{BitAnd, BitOr, Not};
+
+/// A set of notes regardless of octave, one bit per pitch class starting from C, so membership
+/// tests and comparisons are single bitwise operations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NoteSet(pub u16);
+
+impl NoteSet {
+    /// Every pitch class.
+    pub const ALL: NoteSet = NoteSet(0xfff);
+
+    /// The set of just `note`.
+    pub fn of(note: Note) -> NoteSet {
+        NoteSet(1 << note as u16)
+    }
+
+    /// Is `note` in the set.
+    pub fn contains(&self, note: Note) -> bool {
+        self.0 & NoteSet::of(note).0 != 0
+    }
+
+    /// Add `note` to the set.
+    pub fn insert(&mut self, note: Note) {
+        self.0 |= NoteSet::of(note).0;
+    }
+
+    /// How many notes are in the set.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Is the set empty.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl FromIterator<Note> for NoteSet {
+    fn from_iter<I: IntoIterator<Item = Note>>(notes: I) -> Self {
+        let mut set = NoteSet::default();
+        notes.into_iter().for_each(|n| set.insert(n));
+        set
+    }
+}
+
+impl BitOr for NoteSet {
+    type Output = Self;
+
+    /// Notes in either set.
+    fn bitor(self, rhs: Self) -> Self::Output {
+        NoteSet(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for NoteSet {
+    type Output = Self;
+
+    /// Notes in both sets.
+    fn bitand(self, rhs: Self) -> Self::Output {
+        NoteSet(self.0 & rhs.0)
+    }
+}
+
+impl Not for NoteSet {
+    type Output = Self;
+
+    /// Notes not in the set.
+    fn not(self) -> Self::Output {
+        NoteSet(!self.0 & NoteSet::ALL.0)
+    }
+}
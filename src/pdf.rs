@@ -0,0 +1,263 @@
+//! Writing PDF documents of text and line drawings.
+
+use crate::diagram::{Anchor, Canvas};
+use std::fmt::Write;
+
+/// Width and height of an A4 page in points.
+pub(crate) const A4: (f32, f32) = (595.0, 842.0);
+
+/// How far the control points of the Bézier curves approximating a circle are from their ends,
+/// as a fraction of the radius.
+const KAPPA: f32 = 0.5523;
+
+/// Widths of the printable ASCII characters in Helvetica, in thousandths of the font size.
+const HELVETICA: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Widths of the printable ASCII characters in Helvetica Bold.
+const HELVETICA_BOLD: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611, 975, 722, 722, 722, 722, 667,
+    611, 778, 722, 278, 556, 722, 611, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 333, 278, 333, 584, 556, 333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556,
+    278, 889, 611, 611, 611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// The fonts text can be set in, both built into every PDF reader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Font {
+    Regular,
+    Bold,
+}
+
+impl Font {
+    /// How the page resources name the font.
+    fn resource(self) -> &'static str {
+        match self {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        }
+    }
+
+    /// How wide `text` is set in this font `size` high. Characters outside ASCII are printed
+    /// as `?`, so they're measured as one.
+    pub(crate) fn width(self, text: &str, size: f32) -> f32 {
+        let widths = match self {
+            Font::Regular => &HELVETICA,
+            Font::Bold => &HELVETICA_BOLD,
+        };
+        let units: u32 = text
+            .chars()
+            .map(|c| {
+                let i = (c as usize).wrapping_sub(32);
+                u32::from(*widths.get(i).unwrap_or(&widths[b'?' as usize - 32]))
+            })
+            .sum();
+        units as f32 * size / 1000.0
+    }
+}
+
+/// A page of an A4 [`Pdf`], drawn on with the origin at its top left corner.
+pub(crate) struct Page {
+    content: String,
+}
+
+impl Page {
+    pub(crate) fn new() -> Page {
+        // flipped so that y is measured down from the top, like in svg
+        Page {
+            content: format!("1 0 0 -1 0 {} cm\n", A4.1),
+        }
+    }
+
+    /// `text` in `font`, `size` high on a baseline through `at`.
+    pub(crate) fn text_in(
+        &mut self,
+        font: Font,
+        at: (f32, f32),
+        size: f32,
+        anchor: Anchor,
+        text: &str,
+    ) {
+        let x = match anchor {
+            Anchor::Start => at.0,
+            Anchor::Middle => at.0 - font.width(text, size) / 2.0,
+            Anchor::End => at.0 - font.width(text, size),
+        };
+        // flipped back, as text would otherwise be upside down
+        let _ = writeln!(
+            self.content,
+            "BT /{} {size:.2} Tf 1 0 0 -1 {x:.2} {:.2} Tm ({}) Tj ET",
+            font.resource(),
+            at.1,
+            literal(text)
+        );
+    }
+
+    /// Draw with the origin moved to `at` and everything scaled by `scale`.
+    pub(crate) fn placed(&mut self, at: (f32, f32), scale: f32, draw: impl FnOnce(&mut Page)) {
+        let _ = writeln!(
+            self.content,
+            "q {scale:.4} 0 0 {scale:.4} {:.2} {:.2} cm",
+            at.0, at.1
+        );
+        draw(self);
+        self.content.push_str("Q\n");
+    }
+}
+
+impl Canvas for Page {
+    fn line(&mut self, (x1, y1): (f32, f32), (x2, y2): (f32, f32), width: f32) {
+        let _ = writeln!(
+            self.content,
+            "{width:.2} w {x1:.2} {y1:.2} m {x2:.2} {y2:.2} l S"
+        );
+    }
+
+    fn circle(&mut self, (cx, cy): (f32, f32), r: f32, filled: bool) {
+        let k = KAPPA * r;
+        let _ = write!(self.content, "1 w {:.2} {cy:.2} m", cx + r);
+        // a quarter of the circle at a time, clockwise on the page from the right
+        let points = [
+            (cx + r, cy + k),
+            (cx + k, cy + r),
+            (cx, cy + r),
+            (cx - k, cy + r),
+            (cx - r, cy + k),
+            (cx - r, cy),
+            (cx - r, cy - k),
+            (cx - k, cy - r),
+            (cx, cy - r),
+            (cx + k, cy - r),
+            (cx + r, cy - k),
+            (cx + r, cy),
+        ];
+        for quarter in points.chunks(3) {
+            for (x, y) in quarter {
+                let _ = write!(self.content, " {x:.2} {y:.2}");
+            }
+            self.content.push_str(" c");
+        }
+        self.content.push_str(if filled { " f\n" } else { " S\n" });
+    }
+
+    fn curve(&mut self, from: (f32, f32), control: (f32, f32), to: (f32, f32), width: f32) {
+        // the quadratic curve as the cubic one PDF draws
+        let third = |end: (f32, f32)| {
+            (
+                end.0 + 2.0 / 3.0 * (control.0 - end.0),
+                end.1 + 2.0 / 3.0 * (control.1 - end.1),
+            )
+        };
+        let (c1, c2) = (third(from), third(to));
+        let _ = writeln!(
+            self.content,
+            "{width:.2} w {:.2} {:.2} m {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c S",
+            from.0, from.1, c1.0, c1.1, c2.0, c2.1, to.0, to.1
+        );
+    }
+
+    fn text(&mut self, at: (f32, f32), size: f32, anchor: Anchor, white: bool, text: &str) {
+        if white {
+            self.content.push_str("1 g\n");
+        }
+        self.text_in(Font::Regular, at, size, anchor, text);
+        if white {
+            self.content.push_str("0 g\n");
+        }
+    }
+}
+
+/// A PDF document of A4 pages.
+pub(crate) struct Pdf {
+    title: String,
+    pages: Vec<Page>,
+}
+
+impl Pdf {
+    /// An empty document, titled `title` in its metadata.
+    pub(crate) fn new(title: &str) -> Pdf {
+        Pdf {
+            title: title.to_string(),
+            pages: Vec::new(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, page: Page) {
+        self.pages.push(page);
+    }
+
+    /// How many pages there are so far.
+    pub(crate) fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// The bytes of the PDF file.
+    pub(crate) fn finish(self) -> Vec<u8> {
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Count {} /Kids [{}] >>",
+                self.pages.len(),
+                (0..self.pages.len())
+                    .map(|i| format!("{} 0 R", 6 + 2 * i))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            format!("<< /Title ({}) >>", literal(&self.title)),
+        ];
+        for (i, page) in self.pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                A4.0,
+                A4.1,
+                7 + 2 * i
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                page.content.len(),
+                page.content
+            ));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            let _ = write!(pdf, "{} 0 obj\n{}\nendobj\n", i + 1, object);
+        }
+        let xref = pdf.len();
+        let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 5 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        );
+        pdf.into_bytes()
+    }
+}
+
+/// `text` escaped for a PDF string, with characters outside ASCII replaced by `?`.
+fn literal(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{}", c),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
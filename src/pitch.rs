@@ -0,0 +1,90 @@
+//! Absolute pitches, so voicings know which string really sounds lowest.
+
+use crate::{split_note, Finger, Note, ParseError};
+use num_traits::{FromPrimitive, ToPrimitive};
+use serde::Serialize;
+use std::{fmt, ops::Add, str::FromStr};
+
+/// A note in a specific octave, as a MIDI note number (middle C, C4, is 60).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct Pitch(pub u8);
+
+impl Pitch {
+    /// The pitch of `note` in scientific pitch notation `octave`, e.g. E2 for the low guitar string.
+    pub const fn new(note: Note, octave: u8) -> Pitch {
+        Pitch((octave + 1) * 12 + note as u8)
+    }
+
+    /// Pitch class of this pitch.
+    pub fn note(&self) -> Note {
+        Note::from_u8(self.0 % 12).unwrap()
+    }
+
+    /// Octave number, with C4 starting the 4th octave.
+    pub fn octave(&self) -> u8 {
+        (self.0 / 12).saturating_sub(1)
+    }
+
+    /// Frequency of the pitch in hertz, in equal temperament with A4 at 440.
+    pub fn frequency(&self) -> f32 {
+        self.frequency_at(440.0)
+    }
+
+    /// Frequency of the pitch in hertz, in equal temperament with A4 at `reference` hertz, like
+    /// 442 for orchestras tuning high.
+    pub fn frequency_at(&self, reference: f32) -> f32 {
+        reference * 2f32.powf((f32::from(self.0) - 69.0) / 12.0)
+    }
+}
+
+impl Add<u8> for Pitch {
+    type Output = Self;
+
+    /// Transpose up by `rhs` semitones.
+    fn add(self, rhs: u8) -> Self::Output {
+        Pitch(self.0.saturating_add(rhs))
+    }
+}
+
+impl Add<Finger> for Pitch {
+    type Output = Option<Self>;
+
+    /// The pitch sounded by fretting an open string tuned to `self`, or `None` if muted.
+    fn add(self, rhs: Finger) -> Self::Output {
+        rhs.0.map(|val| self + val)
+    }
+}
+
+impl FromStr for Pitch {
+    type Err = ParseError;
+
+    /// Parse a note name followed by its octave, like `"E2"`, `"F#3"` or `"Bb1"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (note, octave) = split_note(s).ok_or_else(|| ParseError::UnknownNote(s.to_string()))?;
+        let octave: u8 = octave
+            .parse()
+            .map_err(|_| ParseError::UnknownNote(s.to_string()))?;
+        // accidentals can cross into the neighbouring octave, Cb4 is B3
+        let letter = split_note(&s[..1]).unwrap().0;
+        let shift = note.to_i32().unwrap() - letter.to_i32().unwrap();
+        let shift = if shift > 6 {
+            shift - 12
+        } else if shift < -6 {
+            shift + 12
+        } else {
+            shift
+        };
+        let midi = Pitch::new(letter, octave).0 as i32 + shift;
+        u8::try_from(midi)
+            .ok()
+            .filter(|&m| m < 128)
+            .map(Pitch)
+            .ok_or_else(|| ParseError::UnknownNote(s.to_string()))
+    }
+}
+
+impl fmt::Display for Pitch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.note().name(), self.octave())
+    }
+}
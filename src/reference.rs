@@ -0,0 +1,105 @@
+//! Well-known fingerings the generator should find, to catch changes to the filters or scoring
+//! that lose them.
+
+use crate::{parse_fret_string, ChordSymbol, Fingering, Pitch, Voicing, DEFAULT_TUNING};
+
+/// A fingering everyone learns early on, and how far down its chord's voicings it may rank.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference {
+    /// The chord, like `"Am"`.
+    pub symbol: &'static str,
+    /// Its frets from the lowest string, written like [`crate::fret_string`].
+    pub frets: &'static str,
+    /// The lowest rank it may have among the chord's voicings with the default options, 1 for the
+    /// best scoring one: the rank it has with today's scoring and a couple to spare, so a change
+    /// that moves it further down shows up.
+    pub within: usize,
+}
+
+/// The tuning of the [`REFERENCES`], standard guitar tuning without a capo.
+pub const REFERENCE_TUNING: &[Pitch] = DEFAULT_TUNING;
+
+/// Open chords, and the barre chords learnt with them.
+pub const REFERENCES: &[Reference] = &[
+    Reference::new("C", "x32010", 7),
+    Reference::new("D", "xx0232", 6),
+    Reference::new("E", "022100", 3),
+    Reference::new("F", "133211", 13),
+    Reference::new("G", "320003", 13),
+    Reference::new("A", "x02220", 4),
+    Reference::new("Bb", "x13331", 10),
+    Reference::new("Dm", "xx0231", 7),
+    Reference::new("Em", "022000", 3),
+    Reference::new("Am", "x02210", 4),
+    Reference::new("Bm", "x24432", 27),
+    Reference::new("C7", "x32310", 16),
+    Reference::new("D7", "xx0212", 6),
+    Reference::new("E7", "020100", 3),
+    Reference::new("G7", "320001", 11),
+    Reference::new("A7", "x02020", 4),
+    Reference::new("B7", "x21202", 13),
+    Reference::new("Cmaj7", "x32000", 5),
+    Reference::new("Fmaj7", "xx3210", 8),
+    Reference::new("Dm7", "xx0211", 5),
+    Reference::new("Em7", "020000", 3),
+    Reference::new("Am7", "x02010", 4),
+    Reference::new("Dsus2", "xx0230", 8),
+    Reference::new("Asus2", "x02200", 4),
+];
+
+impl Reference {
+    const fn new(symbol: &'static str, frets: &'static str, within: usize) -> Reference {
+        Reference {
+            symbol,
+            frets,
+            within,
+        }
+    }
+
+    /// The chord it's a fingering of.
+    pub fn chord(&self) -> ChordSymbol {
+        self.symbol.parse().expect("reference chords are built in")
+    }
+
+    /// Its frets, from the lowest string.
+    pub fn fingering(&self) -> Fingering {
+        parse_fret_string(self.frets).expect("reference frets are written correctly")
+    }
+
+    /// Where it ranks among its chord's `voicings`, 1 for the first, `None` if it isn't one of
+    /// them.
+    pub fn rank(&self, voicings: &[Voicing]) -> Option<usize> {
+        let fingering = self.fingering();
+        voicings
+            .iter()
+            .position(|v| v.frets == fingering)
+            .map(|i| i + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_voicings, Options};
+
+    #[test]
+    fn references_are_generated_near_the_top() {
+        for reference in REFERENCES {
+            let symbol = reference.chord();
+            let options = Options {
+                bass: symbol.bass,
+                ..Options::default()
+            };
+            let voicings = generate_voicings(symbol.root, symbol.chord, REFERENCE_TUNING, &options);
+            let rank = reference.rank(&voicings);
+            assert!(
+                rank.is_some_and(|rank| rank <= reference.within),
+                "{} {} ranked {:?}, should be in the first {}",
+                reference.symbol,
+                reference.frets,
+                rank,
+                reference.within
+            );
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Ranking voicings by how easy they are to play.
+
+use crate::{first_position_frets, reach, Doubling, Finger, Hand, Options, Voicing};
+use serde::Serialize;
+
+/// The points a voicing earns or loses for each part of its score, weighted by
+/// [`Options::weights`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Score {
+    /// All the points earned less the ones lost.
+    pub total: u32,
+    /// Earned for open strings.
+    pub open: u32,
+    /// Earned for fretted strings, more for the ones closer to the nut.
+    pub fretted: u32,
+    /// Earned for muted strings.
+    pub muted: u32,
+    /// Earned for a stretch narrower than [`Options::max_span`].
+    pub span: u32,
+    /// Earned for the fingers left free.
+    pub fingers: u32,
+    /// Lost for holding a barre.
+    pub barre: u32,
+    /// Lost for doubling tones other than the root and fifth.
+    pub doubling: u32,
+    /// Lost for neighbouring strings sounding the same pitch.
+    pub unison: u32,
+}
+
+impl Score {
+    /// The score of `fingering`, sounding the chord tones counted in `doubling`, with `unisons`
+    /// pairs of neighbouring strings sounding the same pitch.
+    pub fn of(
+        fingering: &[Finger],
+        doubling: &Doubling,
+        unisons: usize,
+        options: &Options,
+    ) -> Score {
+        let w = &options.weights;
+        let mut score = Score::default();
+        // prefer compact chords, spans beyond the limit earn nothing
+        let span =
+            first_position_frets(reach(fingering, options.scale_length), options.scale_length);
+        score.span =
+            ((options.max_span as f32 + 2.0 - span).max(0.0) * w.span as f32).round() as u32;
+        // and ones needing fewer fingers
+        if let Some(hand) = Hand::of(fingering, options.thumb) {
+            score.fingers = w.free_finger * 4u32.saturating_sub(hand.count() as u32);
+            if hand.barre.is_some() {
+                score.barre = w.barre;
+            }
+        }
+        for (i, finger) in fingering.iter().enumerate() {
+            if i < options.extra_low_strings && finger.0.is_some() {
+                // Extended range strings make most chords muddy, they only earn points when muted
+                continue;
+            }
+            match finger.0 {
+                // Playing in position, an open string is just as good as muting it
+                Some(0) if options.position.is_some() => score.open += w.open_in_position,
+                // Open strings are best, give em max points :)
+                Some(0) => score.open += w.open,
+                // Closed strings are okay but better to have them at the start of the neck, scaled
+                // so the first fret earns full points and the ones past the highest fret earn
+                // nothing
+                Some(x) => {
+                    let frets = options.max_fret as u32 + 1;
+                    score.fretted += w.fretted * frets.saturating_sub(x as u32) / frets
+                }
+                // Muting is better than playing
+                None => score.muted += w.muted,
+            }
+        }
+        score.doubling = doubling.penalty(w.doubling);
+        score.unison = w.unison * unisons as u32;
+        score.total = (score.open + score.fretted + score.muted + score.span + score.fingers)
+            .saturating_sub(score.barre + score.doubling + score.unison);
+        score
+    }
+}
+
+// TODO: This is temporary, we need a cost function for distance, cramping, crossing etc
+/// Heuristic playability score of a fingering, higher is better. Doublings and unisons aren't
+/// counted, see [`voicing_score`] for those.
+pub fn fingering_score(fingering: &[Finger], options: &Options) -> u32 {
+    Score::of(fingering, &Doubling::default(), 0, options).total
+}
+
+/// Score of a voicing, higher is better: its [`fingering_score`] less its [`Doubling::penalty`].
+pub fn voicing_score(voicing: &Voicing, options: &Options) -> u32 {
+    Score::of(&voicing.frets, &voicing.doubling, voicing.unisons, options).total
+}
+
+/// Ranks the voicings returned by [`generate_voicings_with`](crate::generate_voicings_with).
+pub trait VoicingScorer {
+    /// Score of `v`, higher is better.
+    fn score(&self, v: &Voicing) -> f64;
+}
+
+/// The built-in ranking, see [`voicing_score`].
+impl VoicingScorer for Options {
+    fn score(&self, v: &Voicing) -> f64 {
+        voicing_score(v, self) as f64
+    }
+}
+
+impl<F: Fn(&Voicing) -> f64> VoicingScorer for F {
+    fn score(&self, v: &Voicing) -> f64 {
+        self(v)
+    }
+}
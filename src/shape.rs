@@ -0,0 +1,44 @@
+//! Movable shapes, the same fingering moved up and down the neck for other roots.
+
+use crate::{Finger, Fingering, Note, Voicing};
+use serde::Serialize;
+
+/// A voicing without open strings, which plays the same chord quality on another root when moved
+/// along the neck.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct Shape {
+    /// Fret held on each string, counting from 1 for the lowest fretted one.
+    pub frets: Fingering,
+    /// String sounding the lowest root, numbered from 1 for the highest like
+    /// [`Options::strings`](crate::Options::strings). `None` for rootless shapes.
+    pub root_string: Option<u8>,
+}
+
+impl Shape {
+    /// The shape of `voicing`, of a chord built on `root`, and the fret it's played at. `None`
+    /// for voicings with open strings, which can't be moved.
+    pub fn of(voicing: &Voicing, root: Note) -> Option<(Shape, u8)> {
+        if voicing.frets.iter().any(|f| f.0 == Some(0)) {
+            return None;
+        }
+        let base = voicing.frets.iter().filter_map(|f| f.0).min()?;
+        let frets = voicing
+            .frets
+            .iter()
+            .map(|f| Finger(f.0.map(|x| x - base + 1)))
+            .collect();
+        let root_string = voicing
+            .pitches
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| p.filter(|p| p.note() == root).map(|p| (p, i)))
+            .min()
+            .map(|(_, i)| (voicing.frets.len() - i) as u8);
+        Some((Shape { frets, root_string }, base))
+    }
+
+    /// Reverse the string order, for left-handed players.
+    pub fn mirror(&mut self) {
+        self.frets.reverse();
+    }
+}
@@ -0,0 +1,80 @@
+//! Orders the generated voicings can be listed in.
+
+use crate::{ParseError, Voicing};
+use std::{
+    cmp::{Ordering, Reverse},
+    fmt,
+    str::FromStr,
+};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// What to list voicings by. Voicings that tie keep their score order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, EnumIter)]
+pub enum SortOrder {
+    /// Best score first.
+    #[default]
+    Score,
+    /// Closest to the nut first, by the lowest fretted fret.
+    Position,
+    /// Easiest first, see [`Difficulty`](crate::Difficulty).
+    Difficulty,
+    /// Most open strings first.
+    OpenStrings,
+    /// Grouped by the string with the lowest root, from the lowest string, rootless ones last.
+    /// See [`Voicing::root_string`].
+    RootString,
+}
+
+impl SortOrder {
+    /// Sort `voicings`, which are in score order, stably by this order.
+    pub fn sort(&self, voicings: &mut [Voicing]) {
+        if *self != SortOrder::Score {
+            voicings.sort_by(|a, b| self.cmp(a, b));
+        }
+    }
+
+    /// Which of two voicings comes first in this order, `Equal` if it's left to their scores.
+    pub fn cmp(&self, a: &Voicing, b: &Voicing) -> Ordering {
+        let lowest = |v: &Voicing| v.frets.iter().filter_map(|f| f.0).filter(|&x| x > 0).min();
+        let open = |v: &Voicing| Reverse(v.frets.iter().filter(|f| f.0 == Some(0)).count());
+        match self {
+            SortOrder::Score => Ordering::Equal,
+            SortOrder::Position => lowest(a).cmp(&lowest(b)),
+            SortOrder::Difficulty => a.difficulty.cmp(&b.difficulty),
+            SortOrder::OpenStrings => open(a).cmp(&open(b)),
+            SortOrder::RootString => {
+                let string = |v: &Voicing| v.root_string.map_or(Reverse(0), Reverse);
+                string(a).cmp(&string(b))
+            }
+        }
+    }
+
+    /// Name used on the command line, e.g. `"open-strings"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortOrder::Score => "score",
+            SortOrder::Position => "position",
+            SortOrder::Difficulty => "difficulty",
+            SortOrder::OpenStrings => "open-strings",
+            SortOrder::RootString => "root-string",
+        }
+    }
+}
+
+impl FromStr for SortOrder {
+    type Err = ParseError;
+
+    /// Parse a [`SortOrder::name`], case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SortOrder::iter()
+            .find(|o| o.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseError::UnknownSortOrder(s.to_string()))
+    }
+}
+
+impl fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
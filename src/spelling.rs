@@ -0,0 +1,137 @@
+//! Choosing between sharp and flat names for notes, depending on the key they're used in.
+
+use crate::{Chord, Note, Pitch};
+
+/// Whether black keys are named with sharps (`C#`) or flats (`Db`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Spelling {
+    Sharps,
+    Flats,
+}
+
+impl Note {
+    /// Name of the note using the given accidentals, e.g. `"Eb"` or `"D#"`.
+    pub fn spelled(&self, spelling: Spelling) -> &'static str {
+        match (spelling, self) {
+            (Spelling::Flats, Note::CSharp) => "Db",
+            (Spelling::Flats, Note::DSharp) => "Eb",
+            (Spelling::Flats, Note::FSharp) => "Gb",
+            (Spelling::Flats, Note::GSharp) => "Ab",
+            (Spelling::Flats, Note::ASharp) => "Bb",
+            _ => self.name(),
+        }
+    }
+}
+
+/// Accidentals of the major key on `root`, which is how a root on its own is usually named: Db,
+/// Eb, F#, Ab and Bb for the black keys.
+pub fn key_spelling(root: Note) -> Spelling {
+    match root {
+        Note::G | Note::D | Note::A | Note::E | Note::B | Note::FSharp => Spelling::Sharps,
+        _ => Spelling::Flats,
+    }
+}
+
+/// Accidentals used for the notes of `chord` built on `root`.
+///
+/// Chords with a minor third are spelled like the minor key on their root (C#m, Ebm, G#m, Bbm),
+/// the others like the major key (Db, Eb, F#, Ab, Bb).
+pub fn chord_spelling(root: Note, chord: Chord) -> Spelling {
+    if !chord
+        .tones()
+        .iter()
+        .any(|t| t.degree == 3 && t.semitones == 3)
+    {
+        return key_spelling(root);
+    }
+    match root {
+        Note::E | Note::B | Note::FSharp | Note::CSharp | Note::GSharp | Note::A => {
+            Spelling::Sharps
+        }
+        _ => Spelling::Flats,
+    }
+}
+
+impl Pitch {
+    /// Name of the pitch with its octave using the given accidentals, e.g. `"Eb2"`.
+    pub fn spelled(&self, spelling: Spelling) -> String {
+        format!("{}{}", self.note().spelled(spelling), self.octave())
+    }
+}
+
+/// Names of the notes of `chord` built on `root`, root first, spelled for its key.
+///
+/// Altered fifths and extensions are spelled by the way they were altered, so the b9 of G7b9 is
+/// Ab and the #5 of C+ is G#.
+pub fn spell_chord(root: Note, chord: Chord) -> Vec<&'static str> {
+    let spelling = chord_spelling(root, chord);
+    chord
+        .tones()
+        .iter()
+        .map(|t| {
+            let note = root + t.semitones;
+            match (t.degree, t.alteration()) {
+                (5 | 9 | 11 | 13, a) if a < 0 => note.spelled(Spelling::Flats),
+                (5 | 9 | 11 | 13, a) if a > 0 => note.spelled(Spelling::Sharps),
+                _ => note.spelled(spelling),
+            }
+        })
+        .collect()
+}
+
+/// Name of the note each string sounds in a voicing of `chord` built on `root` sounding
+/// `pitches`, spelled like [`spell_chord`], `None` for muted strings.
+pub fn spell_pitches(
+    root: Note,
+    chord: Chord,
+    pitches: &[Option<Pitch>],
+) -> Vec<Option<&'static str>> {
+    let names = spell_chord(root, chord);
+    let spelling = chord_spelling(root, chord);
+    pitches
+        .iter()
+        .map(|p| {
+            let note = (*p)?.note();
+            Some(
+                chord
+                    .tones()
+                    .iter()
+                    .position(|t| root + t.semitones == note)
+                    .map_or_else(|| note.spelled(spelling), |i| names[i]),
+            )
+        })
+        .collect()
+}
+
+/// Accidentals for naming the open strings of a tuning, taken from the key of its lowest string
+/// so Eb tunings come out as Eb Ab Db Gb Bb Eb and open D as D A D F# A D.
+pub fn tuning_spelling(t: &[Pitch]) -> Spelling {
+    t.first()
+        .map_or(Spelling::Sharps, |p| key_spelling(p.note()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spells_chords_for_their_key() {
+        let spelled = |root, chord| spell_chord(root, chord).join(" ");
+        assert_eq!(spelled(Note::ASharp, Chord::MAJOR), "Bb D F");
+        assert_eq!(spelled(Note::FSharp, Chord::SEVENTH), "F# A# C# E");
+        assert_eq!(spelled(Note::DSharp, Chord::MINOR), "Eb Gb Bb");
+        assert_eq!(spelled(Note::CSharp, Chord::MINOR), "C# E G#");
+        assert_eq!(spelled(Note::G, Chord::SEVENTH_FLAT_NINTH), "G B D F Ab");
+        assert_eq!(spelled(Note::C, Chord::AUGMENTED), "C E G#");
+    }
+
+    #[test]
+    fn spells_tunings_by_their_lowest_string() {
+        let eb: Vec<Pitch> = [(Note::DSharp, 2), (Note::GSharp, 2), (Note::CSharp, 3)]
+            .iter()
+            .map(|&(n, octave)| Pitch::new(n, octave))
+            .collect();
+        let names: Vec<String> = eb.iter().map(|p| p.spelled(tuning_spelling(&eb))).collect();
+        assert_eq!(names, ["Eb2", "Ab2", "Db3"]);
+    }
+}
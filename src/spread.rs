@@ -0,0 +1,109 @@
+//! How spread out the notes of a voicing are.
+
+use crate::{ParseError, Pitch};
+use serde::Serialize;
+use std::{fmt, str::FromStr};
+
+/// Distances between the sounding pitches of a voicing, in semitones.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Spread {
+    /// From the lowest to the highest sounding pitch.
+    pub range: u8,
+    /// Between each pair of adjacent voices, from the bottom up, 0 for unisons.
+    pub gaps: Vec<u8>,
+}
+
+impl Spread {
+    /// The spread of a voicing sounding `pitches`.
+    pub fn of(pitches: &[Option<Pitch>]) -> Spread {
+        let mut sounding: Vec<u8> = pitches.iter().flatten().map(|p| p.0).collect();
+        sounding.sort_unstable();
+        let gaps: Vec<u8> = sounding.windows(2).map(|w| w[1] - w[0]).collect();
+        Spread {
+            range: gaps.iter().sum(),
+            gaps,
+        }
+    }
+
+    /// The widest gap between adjacent voices.
+    pub fn largest_gap(&self) -> u8 {
+        self.gaps.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Whether the voicing fits in an octave.
+    pub fn spacing(&self) -> Spacing {
+        if self.range <= 12 {
+            Spacing::Close
+        } else {
+            Spacing::Open
+        }
+    }
+}
+
+/// Lowest pitch the bottom note of an interval of `semitones` should sit on before it sounds
+/// muddy, following the usual low interval limits of orchestration. `None` for unisons, octaves
+/// and intervals wider than a major ninth.
+pub fn low_interval_limit(semitones: u8) -> Option<Pitch> {
+    use crate::Note::*;
+    let (note, octave) = match semitones {
+        1 => (E, 3),
+        2 => (DSharp, 3),
+        3 => (C, 3),
+        4 | 5 => (ASharp, 2),
+        6 => (B, 2),
+        7 => (ASharp, 1),
+        8 => (G, 2),
+        9..=11 => (F, 2),
+        13 => (E, 2),
+        14 => (D, 2),
+        _ => return None,
+    };
+    Some(Pitch::new(note, octave))
+}
+
+/// Do all adjacent sounding pitches respect their [`low_interval_limit`].
+pub fn within_low_interval_limits(pitches: &[Option<Pitch>]) -> bool {
+    let mut sounding: Vec<Pitch> = pitches.iter().flatten().copied().collect();
+    sounding.sort_unstable();
+    sounding
+        .windows(2)
+        .all(|w| low_interval_limit(w[1].0 - w[0].0).is_none_or(|limit| w[0] >= limit))
+}
+
+/// Whether a voicing's notes are packed within an octave or spread further apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Spacing {
+    /// Spanning an octave at most.
+    Close,
+    /// Spanning more than an octave.
+    Open,
+}
+
+impl Spacing {
+    /// Lowercase name, e.g. `"close"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Spacing::Close => "close",
+            Spacing::Open => "open",
+        }
+    }
+}
+
+impl FromStr for Spacing {
+    type Err = ParseError;
+
+    /// Parse a [`Spacing::name`], case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Spacing::Close, Spacing::Open]
+            .into_iter()
+            .find(|sp| sp.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseError::UnknownSpacing(s.to_string()))
+    }
+}
+
+impl fmt::Display for Spacing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
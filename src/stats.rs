@@ -0,0 +1,158 @@
+//! Counting what the generated voicings are like, to see what filters and scoring weights do.
+
+use crate::{Chord, Difficulty, Voicing};
+use std::{collections::BTreeMap, fmt};
+use strum::IntoEnumIterator;
+
+/// Counts over the voicings of a set of chords, added a chord at a time with [`Stats::add`] and
+/// displayed as a report.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Chords added, and how many of them have no voicings.
+    pub chords: usize,
+    pub without_voicings: usize,
+    pub voicings: usize,
+    /// Chords and voicings of each quality.
+    pub qualities: BTreeMap<Chord, (usize, usize)>,
+    /// Voicings starting at each [`Voicing::base_fret`].
+    pub base_frets: BTreeMap<u8, usize>,
+    /// Frets covered by the fretted notes of each voicing, added up.
+    pub spans: usize,
+    /// Voicings held with a barre, and those of them across every string.
+    pub barres: usize,
+    pub full_barres: usize,
+    /// Voicings ringing at least one open string, and the open strings of all of them.
+    pub with_open: usize,
+    pub open_strings: usize,
+    pub difficulties: BTreeMap<Difficulty, usize>,
+    /// The [`Voicing::score`] totals, added up.
+    pub scores: u64,
+}
+
+impl Stats {
+    /// Count `voicings`, the ones generated for a chord of quality `chord`.
+    pub fn add(&mut self, chord: Chord, voicings: &[Voicing]) {
+        self.chords += 1;
+        if voicings.is_empty() {
+            self.without_voicings += 1;
+        }
+        self.voicings += voicings.len();
+        let quality = self.qualities.entry(chord).or_default();
+        quality.0 += 1;
+        quality.1 += voicings.len();
+        for v in voicings {
+            *self.base_frets.entry(v.base_fret).or_default() += 1;
+            let fretted = v.frets.iter().filter_map(|f| f.0).filter(|&f| f > 0);
+            if let (Some(lo), Some(hi)) = (fretted.clone().min(), fretted.max()) {
+                self.spans += usize::from(hi - lo + 1);
+            }
+            if let Some(barre) = &v.barre {
+                self.barres += 1;
+                self.full_barres += usize::from(barre.full);
+            }
+            let open = v.frets.iter().filter(|f| f.0 == Some(0)).count();
+            self.with_open += usize::from(open > 0);
+            self.open_strings += open;
+            *self.difficulties.entry(v.difficulty).or_default() += 1;
+            self.scores += u64::from(v.score.total);
+        }
+    }
+}
+
+/// `part` as a percentage of `whole`, 0 when there's nothing to count.
+fn percent(part: usize, whole: usize) -> f64 {
+    average(part as f64 * 100.0, whole)
+}
+
+/// The average of `count` things adding up to `total`, 0 when there are none.
+fn average(total: f64, count: usize) -> f64 {
+    if count == 0 {
+        0.0
+    } else {
+        total / count as f64
+    }
+}
+
+/// A report of the counts, a table of the qualities and one of the base frets, then averages and
+/// percentages of the voicings.
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} chords, {} without voicings",
+            self.chords, self.without_voicings
+        )?;
+        writeln!(
+            f,
+            "{} voicings, {:.1} per chord",
+            self.voicings,
+            average(self.voicings as f64, self.chords)
+        )?;
+
+        let width = self
+            .qualities
+            .keys()
+            .map(|c| c.name().len())
+            .max()
+            .unwrap_or(0)
+            .max("quality".len());
+        writeln!(
+            f,
+            "\n{:<width$}  chords  voicings",
+            "quality",
+            width = width
+        )?;
+        for (chord, (chords, voicings)) in &self.qualities {
+            writeln!(
+                f,
+                "{:<width$}  {:>6}  {:>8}",
+                chord.name(),
+                chords,
+                voicings,
+                width = width
+            )?;
+        }
+
+        writeln!(f, "\nbase fret  voicings")?;
+        for (fret, count) in &self.base_frets {
+            writeln!(
+                f,
+                "{:>9}  {:>8}  {:>5.1}%",
+                fret,
+                count,
+                percent(*count, self.voicings)
+            )?;
+        }
+
+        writeln!(f)?;
+        writeln!(
+            f,
+            "average span   {:.1} frets",
+            average(self.spans as f64, self.voicings)
+        )?;
+        writeln!(
+            f,
+            "barres         {:.1}%, {:.1}% across every string",
+            percent(self.barres, self.voicings),
+            percent(self.full_barres, self.voicings)
+        )?;
+        writeln!(
+            f,
+            "open strings   {:.1}% of voicings, {:.1} in each",
+            percent(self.with_open, self.voicings),
+            average(self.open_strings as f64, self.voicings)
+        )?;
+        let difficulties: Vec<String> = Difficulty::iter()
+            .map(|d| {
+                let count = self.difficulties.get(&d).copied().unwrap_or(0);
+                format!("{} {:.1}%", d.name(), percent(count, self.voicings))
+            })
+            .collect();
+        writeln!(f, "difficulty     {}", difficulties.join(", "))?;
+        write!(
+            f,
+            "average score  {:.1}",
+            average(self.scores as f64, self.voicings)
+        )
+    }
+}
@@ -0,0 +1,75 @@
+//! Drawing voicings as SVG chord diagrams.
+
+use crate::diagram::{diagram_size, draw_diagram, Anchor, Canvas};
+use crate::Voicing;
+
+/// A standalone SVG chord diagram of `v` titled `title`, with the strings running down from the
+/// lowest on the left like [`ascii_diagram`](crate::ascii_diagram). It shows the nut, or the fret
+/// the diagram starts at further up the neck, muted and open strings, a dot numbered with the
+/// finger holding each fretted note, and an arc over the strings of a barre.
+pub fn svg_diagram(v: &Voicing, title: &str) -> String {
+    let (width, height) = diagram_size(v);
+    let mut svg = Svg(format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif">"#
+    ) + "\n");
+    draw_diagram(&mut svg, v, title);
+    svg.0 + "</svg>\n"
+}
+
+/// The elements of an SVG image, one per line.
+struct Svg(String);
+
+impl Svg {
+    fn push(&mut self, element: String) {
+        self.0.push_str(&element);
+        self.0.push('\n');
+    }
+}
+
+impl Canvas for Svg {
+    fn line(&mut self, (x1, y1): (f32, f32), (x2, y2): (f32, f32), width: f32) {
+        self.push(format!(
+            r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="black" stroke-width="{width}"/>"#
+        ));
+    }
+
+    fn circle(&mut self, (cx, cy): (f32, f32), r: f32, filled: bool) {
+        self.push(if filled {
+            format!(r#"<circle cx="{cx}" cy="{cy}" r="{r}" fill="black"/>"#)
+        } else {
+            format!(r#"<circle cx="{cx}" cy="{cy}" r="{r}" fill="none" stroke="black"/>"#)
+        });
+    }
+
+    fn curve(
+        &mut self,
+        (x1, y1): (f32, f32),
+        (cx, cy): (f32, f32),
+        (x2, y2): (f32, f32),
+        width: f32,
+    ) {
+        self.push(format!(
+            r#"<path d="M {x1} {y1} Q {cx} {cy} {x2} {y2}" fill="none" stroke="black" stroke-width="{width}"/>"#
+        ));
+    }
+
+    fn text(&mut self, (x, y): (f32, f32), size: f32, anchor: Anchor, white: bool, text: &str) {
+        let anchor = match anchor {
+            Anchor::Start => "start",
+            Anchor::Middle => "middle",
+            Anchor::End => "end",
+        };
+        let fill = if white { r#" fill="white""# } else { "" };
+        self.push(format!(
+            r#"<text x="{x}" y="{y}" font-size="{size}" text-anchor="{anchor}"{fill}>{}</text>"#,
+            escape(text)
+        ));
+    }
+}
+
+/// Escape the characters XML treats specially in text.
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
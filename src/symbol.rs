@@ -0,0 +1,177 @@
+//! Parsing conventional chord symbols like `"F#m7"` or `"Bbmaj9"`.
+
+use crate::{chord_spelling, Chord, Note, ParseError, Spelling};
+use num_traits::{FromPrimitive, ToPrimitive};
+use std::{fmt, str::FromStr};
+
+/// Parse a quality suffix of a chord symbol, e.g. `"m7"`, `"Δ7"` or `"°"`.
+///
+/// The canonical [`Chord::symbol`], its aliases and names like `"MajorSeventh"` are accepted.
+pub fn parse_quality(s: &str) -> Result<Chord, ParseError> {
+    parse_quality_in(s, &Chord::builtins().collect::<Vec<_>>())
+}
+
+/// Parse a quality suffix like [`parse_quality`], out of the given `chords`, e.g. the built-in
+/// ones and some defined with [`Chord::custom`]. Earlier chords win if spellings clash.
+pub fn parse_quality_in(s: &str, chords: &[Chord]) -> Result<Chord, ParseError> {
+    chords
+        .iter()
+        .find(|c| c.symbol() == s)
+        .or_else(|| chords.iter().find(|c| c.aliases().contains(&s)))
+        .or_else(|| chords.iter().find(|c| c.name().eq_ignore_ascii_case(s)))
+        .copied()
+        .ok_or_else(|| ParseError::UnknownChord(s.to_string()))
+}
+
+/// Split a leading note name (a letter and any number of `#`/`b` accidentals) off `s`.
+///
+/// Returns `None` if `s` doesn't start with a note letter.
+pub fn split_note(s: &str) -> Option<(Note, &str)> {
+    let mut chars = s.char_indices();
+    let (_, letter) = chars.next()?;
+    let natural = match letter.to_ascii_uppercase() {
+        'C' => Note::C,
+        'D' => Note::D,
+        'E' => Note::E,
+        'F' => Note::F,
+        'G' => Note::G,
+        'A' => Note::A,
+        'B' => Note::B,
+        _ => return None,
+    };
+    let mut semitones = natural.to_i32().unwrap();
+    let mut rest = &s[letter.len_utf8()..];
+    for (i, c) in chars {
+        match c {
+            '#' | '♯' => semitones += 1,
+            'b' | '♭' => semitones -= 1,
+            _ => break,
+        }
+        rest = &s[i + c.len_utf8()..];
+    }
+    Some((Note::from_i32(semitones.rem_euclid(12)).unwrap(), rest))
+}
+
+/// A chord written the usual way, a root note followed by a quality suffix and optionally a
+/// slash and the bass note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ChordSymbol {
+    pub root: Note,
+    pub chord: Chord,
+    /// Bass note of a slash chord like D/F#.
+    pub bass: Option<Note>,
+}
+
+impl ChordSymbol {
+    /// Parse a chord symbol like [`ChordSymbol::from_str`], with a quality out of `chords`, see
+    /// [`parse_quality_in`].
+    pub fn parse_in(s: &str, chords: &[Chord]) -> Result<ChordSymbol, ParseError> {
+        let s = s.trim();
+        // only a note after the last slash makes it a slash chord, C6/9 is a quality of its own
+        let (s, bass) = match s.rsplit_once('/') {
+            Some((chord, bass)) => match bass.parse::<Note>() {
+                Ok(bass) => (chord, Some(bass)),
+                Err(_) => (s, None),
+            },
+            None => (s, None),
+        };
+        let (root, quality) =
+            split_note(s).ok_or_else(|| ParseError::UnknownNote(s.to_string()))?;
+        Ok(ChordSymbol {
+            root,
+            chord: parse_quality_in(quality, chords)?,
+            bass,
+        })
+    }
+
+    /// The symbol with its black keys named the other way, like C#m for Dbm, for finding it in
+    /// books that spell it differently. `None` if it only has white keys.
+    pub fn respelled(&self) -> Option<String> {
+        let other = match chord_spelling(self.root, self.chord) {
+            Spelling::Sharps => Spelling::Flats,
+            Spelling::Flats => Spelling::Sharps,
+        };
+        let black = |n: Note| n.spelled(Spelling::Sharps) != n.spelled(Spelling::Flats);
+        if !black(self.root) && !self.bass.is_some_and(black) {
+            return None;
+        }
+        let mut s = format!("{}{}", self.root.spelled(other), self.chord.symbol());
+        if let Some(bass) = self.bass {
+            s += &format!("/{}", bass.spelled(other));
+        }
+        Some(s)
+    }
+}
+
+impl FromStr for ChordSymbol {
+    type Err = ParseError;
+
+    /// Parse a chord symbol like `"C"`, `"F#m7"`, `"Bbmaj9"`, `"E°7"` or `"D/F#"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ChordSymbol::parse_in(s, &Chord::builtins().collect::<Vec<_>>())
+    }
+}
+
+impl fmt::Display for ChordSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let spelling = chord_spelling(self.root, self.chord);
+        write!(f, "{}{}", self.root.spelled(spelling), self.chord.symbol())?;
+        if let Some(bass) = self.bass {
+            write!(f, "/{}", bass.spelled(spelling))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> ChordSymbol {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_sharps_and_flats() {
+        assert_eq!(parse("F#m7").root, Note::FSharp);
+        assert_eq!(parse("F#m7").chord, Chord::MINOR_SEVENTH);
+        assert_eq!(parse("Bbmaj9").root, Note::ASharp);
+        assert_eq!(parse("Bbmaj9").chord, Chord::MAJOR_NINTH);
+        assert_eq!(parse("E♭").root, Note::DSharp);
+        assert_eq!(parse("Cb").root, Note::B);
+    }
+
+    #[test]
+    fn parses_aliases() {
+        assert_eq!(parse("C").chord, Chord::MAJOR);
+        assert_eq!(parse("CΔ7").chord, Chord::MAJOR_SEVENTH);
+        assert_eq!(parse("E°").chord, Chord::DIMINISHED);
+        assert_eq!(parse("Cm7b5").chord, Chord::HALF_DIMINISHED);
+    }
+
+    #[test]
+    fn six_nine_is_not_a_slash_chord() {
+        let symbol = parse("C6/9");
+        assert_eq!(symbol.chord, Chord::ADD_SIXTH_ADD_NINTH);
+        assert_eq!(symbol.bass, None);
+    }
+
+    #[test]
+    fn parses_slash_bass() {
+        let symbol = parse("D/F#");
+        assert_eq!(symbol.root, Note::D);
+        assert_eq!(symbol.chord, Chord::MAJOR);
+        assert_eq!(symbol.bass, Some(Note::FSharp));
+        assert_eq!(parse("Am7/G").bass, Some(Note::G));
+        assert_eq!(parse("D/F#").to_string(), "D/F#");
+    }
+
+    #[test]
+    fn rejects_malformed_symbols() {
+        let error = |s: &str| s.parse::<ChordSymbol>().unwrap_err().to_string();
+        assert_eq!(error("H7"), "unknown note 'H7'");
+        assert_eq!(error(""), "unknown note ''");
+        assert_eq!(error("Cfoo"), "unknown chord quality 'foo'");
+        assert_eq!(error("C/X"), "unknown chord quality '/X'");
+    }
+}
@@ -0,0 +1,113 @@
+//! Synthesizing the sound of voicings, to hear them before learning them.
+
+use crate::{ParseError, Voicing};
+use std::{fmt, str::FromStr};
+
+/// Samples a second.
+pub const SAMPLE_RATE: u32 = 44_100;
+/// How much of a plucked string's sound is left after each pass around its delay line.
+const DECAY: f32 = 0.996;
+
+/// How the strings of a voicing are played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Strum {
+    /// All at once.
+    Block,
+    /// Strummed down from the lowest string.
+    #[default]
+    Down,
+    /// Strummed up from the highest string.
+    Up,
+    /// Picked one at a time from the lowest string, each left ringing.
+    Arpeggio,
+}
+
+impl Strum {
+    /// Lowercase name, e.g. `"down"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strum::Block => "block",
+            Strum::Down => "down",
+            Strum::Up => "up",
+            Strum::Arpeggio => "arpeggio",
+        }
+    }
+
+    /// Seconds after the first string is played that string `i` of `strings` is.
+    fn onset(&self, i: usize, strings: usize) -> f32 {
+        match self {
+            Strum::Block => 0.0,
+            Strum::Down => i as f32 * 0.03,
+            Strum::Up => (strings - 1 - i) as f32 * 0.03,
+            Strum::Arpeggio => i as f32 * 0.25,
+        }
+    }
+}
+
+impl FromStr for Strum {
+    type Err = ParseError;
+
+    /// Parse a [`Strum::name`], case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [Strum::Block, Strum::Down, Strum::Up, Strum::Arpeggio]
+            .into_iter()
+            .find(|st| st.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseError::UnknownStrum(s.to_string()))
+    }
+}
+
+impl fmt::Display for Strum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Mono samples of `v` played with `strum` for `seconds`, each string plucked with
+/// [`pluck`]. Strings are played in the order of `v.pitches`, so `v` mustn't be mirrored for
+/// left-handed players.
+pub fn chord_samples(v: &Voicing, strum: Strum, seconds: f32) -> Vec<f32> {
+    let len = (seconds * SAMPLE_RATE as f32) as usize;
+    let sounding = v.pitches.iter().flatten().count();
+    // loud enough without clipping however many strings sound
+    let gain = 0.6 / sounding.max(1) as f32;
+    let mut samples = vec![0.0; len];
+    for (i, p) in v.pitches.iter().enumerate() {
+        let Some(p) = p else { continue };
+        let start = (strum.onset(i, v.pitches.len()) * SAMPLE_RATE as f32) as usize;
+        if start >= len {
+            continue;
+        }
+        let string = pluck(p.frequency(), len - start, i as u32 + 1);
+        for (sample, s) in samples[start..].iter_mut().zip(string) {
+            *sample += gain * s;
+        }
+    }
+    samples
+}
+
+/// `len` samples of a string plucked at `frequency`, by the Karplus–Strong algorithm: a burst
+/// of noise circulating through a delay line a period long, averaging neighbouring samples on
+/// each pass so the high harmonics die away first, like they do on a real string. `seed` picks
+/// the noise, so strings sounding the same note don't cancel out.
+pub fn pluck(frequency: f32, len: usize, seed: u32) -> Vec<f32> {
+    // averaging delays the sound by half a sample more
+    let period = ((SAMPLE_RATE as f32 / frequency - 0.5).round() as usize).max(2);
+    let mut state = seed.wrapping_mul(2_654_435_761).max(1);
+    let mut line: Vec<f32> = (0..period)
+        .map(|_| {
+            // xorshift
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f32 / u32::MAX as f32 * 2.0 - 1.0
+        })
+        .collect();
+    (0..len)
+        .map(|n| {
+            let i = n % period;
+            let sample = line[i];
+            line[i] = DECAY * 0.5 * (sample + line[(i + 1) % period]);
+            sample
+        })
+        .collect()
+}
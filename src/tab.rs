@@ -0,0 +1,176 @@
+//! The compact fret strings chord charts name fingerings by, like `x32010` for a C.
+
+use crate::{Finger, Fingering, ParseError};
+use std::str::FromStr;
+
+/// The frets of `frets` from the lowest string, `x` for muted, like `x32010`. When a fret is 10
+/// or higher the strings are separated by `-` so they can be told apart, like `x-10-12-12-11-x`.
+pub fn fret_string(frets: &[Finger]) -> String {
+    let strings: Vec<String> = frets
+        .iter()
+        .map(|f| f.0.map_or("x".to_string(), |fret| fret.to_string()))
+        .collect();
+    if frets.iter().any(|f| f.0.is_some_and(|fret| fret > 9)) {
+        strings.join("-")
+    } else {
+        strings.concat()
+    }
+}
+
+/// The fingering written as `s` by [`fret_string`]. Strings may also be separated by spaces or
+/// commas, and muted ones written as `X`.
+pub fn parse_fret_string(s: &str) -> Result<Fingering, ParseError> {
+    let invalid = || ParseError::InvalidFretString(s.to_string());
+    let s = s.trim();
+    let strings: Vec<&str> = if s.contains(['-', ' ', ',']) {
+        s.split(['-', ' ', ',']).filter(|f| !f.is_empty()).collect()
+    } else {
+        s.char_indices()
+            .map(|(i, c)| &s[i..i + c.len_utf8()])
+            .collect()
+    };
+    if strings.is_empty() {
+        return Err(invalid());
+    }
+    strings
+        .into_iter()
+        .map(|f| match f {
+            "x" | "X" => Ok(Finger(None)),
+            fret => fret
+                .parse()
+                .map(|fret| Finger(Some(fret)))
+                .map_err(|_| invalid()),
+        })
+        .collect()
+}
+
+/// What a string of a [`FretPattern`] may play.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StringPattern {
+    /// Any fret, or muted.
+    Any,
+    Muted,
+    /// A fret from the first to the second, 0 for the open string.
+    Frets(u8, u8),
+}
+
+/// A query for fingerings like `x 3-5 * * 0 x`, written like a [`fret_string`] from the lowest
+/// string with `*` for a string that may play anything and `3-5` for one held between frets 3
+/// and 5. The strings are separated by spaces or commas, or may be run together when each is a
+/// single character like `x3**0x`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FretPattern(pub Vec<StringPattern>);
+
+impl FretPattern {
+    /// Whether `frets` plays what the pattern asks on each of its strings.
+    pub fn matches(&self, frets: &[Finger]) -> bool {
+        frets.len() == self.0.len()
+            && self.0.iter().zip(frets).all(|(p, f)| match (*p, f.0) {
+                (StringPattern::Any, _) | (StringPattern::Muted, None) => true,
+                (StringPattern::Frets(lo, hi), Some(fret)) => (lo..=hi).contains(&fret),
+                _ => false,
+            })
+    }
+}
+
+impl FromStr for FretPattern {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<FretPattern, ParseError> {
+        let invalid = || ParseError::InvalidFretPattern(s.to_string());
+        let s = s.trim();
+        let strings: Vec<&str> = if s.contains([' ', ',']) {
+            s.split([' ', ',']).filter(|f| !f.is_empty()).collect()
+        } else {
+            s.char_indices()
+                .map(|(i, c)| &s[i..i + c.len_utf8()])
+                .collect()
+        };
+        if strings.is_empty() {
+            return Err(invalid());
+        }
+        strings
+            .into_iter()
+            .map(|f| match f {
+                "*" => Ok(StringPattern::Any),
+                "x" | "X" => Ok(StringPattern::Muted),
+                frets => {
+                    let (lo, hi) = frets.split_once('-').unwrap_or((frets, frets));
+                    match (lo.parse(), hi.parse()) {
+                        (Ok(lo), Ok(hi)) if lo <= hi => Ok(StringPattern::Frets(lo, hi)),
+                        _ => Err(invalid()),
+                    }
+                }
+            })
+            .collect::<Result<_, _>>()
+            .map(FretPattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frets(frets: &[Option<u8>]) -> Fingering {
+        frets.iter().map(|&f| Finger(f)).collect()
+    }
+
+    #[test]
+    fn writes_fret_strings() {
+        let c = frets(&[None, Some(3), Some(2), Some(0), Some(1), Some(0)]);
+        assert_eq!(fret_string(&c), "x32010");
+        let high = frets(&[None, Some(10), Some(12), Some(12), Some(11), None]);
+        assert_eq!(fret_string(&high), "x-10-12-12-11-x");
+    }
+
+    #[test]
+    fn parses_fret_strings() {
+        let c = frets(&[None, Some(3), Some(2), Some(0), Some(1), Some(0)]);
+        assert_eq!(parse_fret_string("x32010").unwrap(), c);
+        assert_eq!(parse_fret_string("X32010").unwrap(), c);
+        assert_eq!(parse_fret_string("x 3 2 0 1 0").unwrap(), c);
+        assert_eq!(parse_fret_string(" x,3,2,0,1,0 ").unwrap(), c);
+        let high = frets(&[None, Some(10), Some(12), Some(12), Some(11), None]);
+        assert_eq!(parse_fret_string("x-10-12-12-11-x").unwrap(), high);
+        assert_eq!(parse_fret_string(&fret_string(&high)).unwrap(), high);
+    }
+
+    #[test]
+    fn rejects_malformed_fret_strings() {
+        let error = |s: &str| parse_fret_string(s).unwrap_err().to_string();
+        assert_eq!(error(""), "invalid fret string ''");
+        assert_eq!(error("x3201a"), "invalid fret string 'x3201a'");
+        assert_eq!(error("x-300-2"), "invalid fret string 'x-300-2'");
+    }
+
+    #[test]
+    fn parses_fret_patterns() {
+        use StringPattern::*;
+        let pattern = vec![Muted, Frets(3, 5), Any, Any, Frets(0, 0), Muted];
+        assert_eq!("x 3-5 * * 0 x".parse::<FretPattern>().unwrap().0, pattern);
+        assert_eq!("x,3-5,*,*,0,X".parse::<FretPattern>().unwrap().0, pattern);
+        let together = vec![Muted, Frets(3, 3), Any, Any, Frets(0, 0), Muted];
+        assert_eq!("x3**0x".parse::<FretPattern>().unwrap().0, together);
+        let high = "x 10-12 * * * *".parse::<FretPattern>().unwrap();
+        assert_eq!(high.0[1], Frets(10, 12));
+    }
+
+    #[test]
+    fn matches_fret_patterns() {
+        let pattern: FretPattern = "x 3-5 * * 0 x".parse().unwrap();
+        assert!(pattern.matches(&parse_fret_string("x4210x").unwrap()));
+        assert!(pattern.matches(&parse_fret_string("x5xx0x").unwrap()));
+        assert!(!pattern.matches(&parse_fret_string("x2210x").unwrap()));
+        assert!(!pattern.matches(&parse_fret_string("x3x0x").unwrap()));
+        assert!(!pattern.matches(&parse_fret_string("x3xx00").unwrap()));
+    }
+
+    #[test]
+    fn rejects_malformed_fret_patterns() {
+        let error = |s: &str| s.parse::<FretPattern>().unwrap_err().to_string();
+        assert_eq!(error(""), "invalid fret pattern ''");
+        assert_eq!(error("x 5-3 * *"), "invalid fret pattern 'x 5-3 * *'");
+        assert_eq!(error("x 3- * *"), "invalid fret pattern 'x 3- * *'");
+        assert_eq!(error("x3?*0x"), "invalid fret pattern 'x3?*0x'");
+    }
+}
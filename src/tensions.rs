@@ -0,0 +1,122 @@
+//! Which chord tones a voicing plays, and the tensions it could add on the strings left over.
+
+use crate::{is_compact, sounded_pitch, string_start, Chord, Finger, Note, Options, Pitch};
+use serde::Serialize;
+
+/// Tensions above the octave as they're written, with their semitones above the root.
+const TENSIONS: [(&str, u8); 7] = [
+    ("b9", 13),
+    ("9", 14),
+    ("#9", 15),
+    ("11", 17),
+    ("#11", 18),
+    ("b13", 20),
+    ("13", 21),
+];
+
+/// The chord tones of a voicing and the tensions that could be added to it, see
+/// [`Tensions::of`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Tensions {
+    /// Chord tones the voicing plays, by their interval above the root like `b7`.
+    pub present: Vec<String>,
+    /// Chord tones it leaves out.
+    pub omitted: Vec<String>,
+    /// Tensions a muted string could add, each on the strings it can be played on.
+    pub available: Vec<AddedTension>,
+}
+
+/// A tension that can be added to a voicing by playing a string it mutes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AddedTension {
+    /// The tension, like `9` or `#11`.
+    pub tension: &'static str,
+    /// String to play it on, numbered from 1 for the highest like [`Options::strings`].
+    pub string: u8,
+    /// Fret to hold, the lowest one that sounds it within [`Options::max_span`] of the other
+    /// fretted notes.
+    pub fret: u8,
+}
+
+/// The tensions that go with `chord` built on `root` and aren't already in it. A tension a
+/// semitone above a chord tone clashes with it and is avoided, except for the altered b9 and b13
+/// of dominant chords. #9 and #11 are only played over a major third.
+pub fn chord_tensions(root: Note, chord: Chord) -> Vec<(&'static str, Note)> {
+    let tones = chord.tones();
+    let has = |degree, semitones| {
+        tones
+            .iter()
+            .any(|t| t.degree == degree && t.semitones % 12 == semitones)
+    };
+    let major_third = has(3, 4);
+    let dominant = major_third && has(7, 10);
+    let notes = chord.notes(root);
+    TENSIONS
+        .iter()
+        .map(|&(name, semitones)| (name, root + semitones))
+        .filter(|&(name, note)| {
+            let clashes = notes.iter().any(|&n| n + 1 == note);
+            let altered = dominant && matches!(name, "b9" | "b13");
+            !notes.contains(&note)
+                && (!clashes || altered)
+                && (major_third || !matches!(name, "#9" | "#11"))
+        })
+        .collect()
+}
+
+impl Tensions {
+    /// The chord tones of `chord` built on `root` that `frets` play in tuning `t`, sounding
+    /// `pitches`, and the tensions that each of its muted strings could add at a fret within
+    /// reach of the others, up to [`Options::max_fret`], or open unless
+    /// [`Options::open_strings`] rules them out.
+    pub fn of(
+        root: Note,
+        chord: Chord,
+        t: &[Pitch],
+        frets: &[Finger],
+        pitches: &[Option<Pitch>],
+        options: &Options,
+    ) -> Tensions {
+        let played: Vec<Note> = pitches.iter().flatten().map(|p| p.note()).collect();
+        let (present, omitted): (Vec<Note>, Vec<Note>) = chord
+            .notes(root)
+            .into_iter()
+            .partition(|n| played.contains(n));
+        let interval = |n: Note| chord.interval(root, n);
+        let tensions = chord_tensions(root, chord);
+        let mut available = Vec::new();
+        for (i, f) in frets.iter().enumerate() {
+            if f.0.is_some() {
+                continue;
+            }
+            let start = string_start(&options.string_starts, i);
+            let open = options.open_strings != Some(false);
+            let candidates = open
+                .then_some(0)
+                .into_iter()
+                .chain(start + 1..=options.max_fret);
+            for &(tension, note) in &tensions {
+                let fret = candidates.clone().find(|&fret| {
+                    let mut added = frets.to_vec();
+                    added[i] = Finger(Some(fret));
+                    let sounded = sounded_pitch(t[i], added[i], start);
+                    sounded.is_some_and(|p| p.note() == note)
+                        && is_compact(&added, options.max_span, options.scale_length)
+                });
+                if let Some(fret) = fret {
+                    available.push(AddedTension {
+                        tension,
+                        string: (frets.len() - i) as u8,
+                        fret,
+                    });
+                }
+            }
+        }
+        available.sort_by_key(|a| a.string);
+        Tensions {
+            present: present.into_iter().map(interval).collect(),
+            omitted: omitted.into_iter().map(interval).collect(),
+            available,
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Describing how a voicing sounds from how its pitches are stacked.
+
+use crate::{Chord, Note, ParseError, Pitch, Spread};
+use serde::Serialize;
+use std::{fmt, str::FromStr};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// A way the pitches of a voicing are laid out that changes how it sounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, EnumIter)]
+#[serde(rename_all = "kebab-case")]
+pub enum Texture {
+    /// The root on two strings or more, for a fuller sound.
+    DoubledRoot,
+    /// The three notes of a triad spread over more than an octave.
+    SpreadTriad,
+    /// Neighbouring voices a semitone apart, rubbing against each other.
+    Cluster,
+    /// The third in more than one octave, which stands out.
+    OctaveDoubledThird,
+    /// More than an octave between the bass and the next voice up.
+    WideBassGap,
+}
+
+impl Texture {
+    /// The textures of a voicing of `chord` built on `root` sounding `pitches`.
+    pub fn of(root: Note, chord: Chord, pitches: &[Option<Pitch>]) -> Vec<Texture> {
+        let sounding: Vec<Pitch> = pitches.iter().flatten().copied().collect();
+        let spread = Spread::of(pitches);
+        let mut notes: Vec<Note> = sounding.iter().map(|p| p.note()).collect();
+        notes.sort_unstable();
+        notes.dedup();
+        let third = chord
+            .tones()
+            .iter()
+            .find(|t| t.degree == 3)
+            .map(|t| root + t.semitones);
+        let mut thirds: Vec<Pitch> = sounding
+            .iter()
+            .copied()
+            .filter(|p| Some(p.note()) == third)
+            .collect();
+        thirds.sort_unstable();
+        thirds.dedup();
+        Texture::iter()
+            .filter(|texture| match texture {
+                Texture::DoubledRoot => sounding.iter().filter(|p| p.note() == root).count() > 1,
+                Texture::SpreadTriad => {
+                    chord.tones().len() == 3 && notes.len() == 3 && spread.range > 12
+                }
+                Texture::Cluster => spread.gaps.contains(&1),
+                Texture::OctaveDoubledThird => thirds.len() > 1,
+                Texture::WideBassGap => spread.gaps.first().is_some_and(|&gap| gap > 12),
+            })
+            .collect()
+    }
+
+    /// Lowercase name, e.g. `"doubled-root"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Texture::DoubledRoot => "doubled-root",
+            Texture::SpreadTriad => "spread-triad",
+            Texture::Cluster => "cluster",
+            Texture::OctaveDoubledThird => "octave-doubled-third",
+            Texture::WideBassGap => "wide-bass-gap",
+        }
+    }
+}
+
+impl FromStr for Texture {
+    type Err = ParseError;
+
+    /// Parse a [`Texture::name`], case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Texture::iter()
+            .find(|t| t.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| ParseError::UnknownTexture(s.to_string()))
+    }
+}
+
+impl fmt::Display for Texture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
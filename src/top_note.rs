@@ -0,0 +1,58 @@
+//! Requiring a melody note on top of a voicing.
+
+use crate::{Chord, Note, ParseError, Pitch};
+use std::{fmt, str::FromStr};
+
+/// What the highest sounding note of a voicing has to be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TopNote {
+    /// Exactly this pitch, e.g. E4.
+    Pitch(Pitch),
+    /// This note in any octave.
+    Note(Note),
+    /// The chord tone of this scale degree, e.g. 3 for the third whether it's major or minor.
+    Degree(u8),
+}
+
+impl TopNote {
+    /// Is `top` the wanted top note of `chord` built on `root`.
+    pub fn matches(&self, root: Note, chord: Chord, top: Pitch) -> bool {
+        match *self {
+            TopNote::Pitch(p) => top == p,
+            TopNote::Note(n) => top.note() == n,
+            TopNote::Degree(d) => chord
+                .tones()
+                .iter()
+                .any(|t| t.degree == d && root + t.semitones == top.note()),
+        }
+    }
+}
+
+impl FromStr for TopNote {
+    type Err = ParseError;
+
+    /// Parse a pitch like `"E4"`, a note like `"F#"`, or a degree like `"3"` or `"3rd"`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let degree = s.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+        if !degree.is_empty() && degree.chars().all(|c| c.is_ascii_digit()) {
+            return match degree.parse() {
+                Ok(d @ 1..=13) => Ok(TopNote::Degree(d)),
+                _ => Err(ParseError::UnknownNote(s.to_string())),
+            };
+        }
+        s.parse()
+            .map(TopNote::Pitch)
+            .or_else(|_| s.parse().map(TopNote::Note))
+    }
+}
+
+impl fmt::Display for TopNote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TopNote::Pitch(p) => write!(f, "{}", p),
+            TopNote::Note(n) => f.write_str(n.name()),
+            TopNote::Degree(d) => write!(f, "{}", d),
+        }
+    }
+}
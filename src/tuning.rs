@@ -0,0 +1,196 @@
+//! Guitar tunings and the built-in preset table.
+
+use crate::{Note, ParseError, Pitch};
+use Note::*;
+
+/// Open-string pitches, from the lowest string to the highest. Any number of strings is allowed.
+///
+/// "Lowest" is the string's position across the neck, which for re-entrant tunings like the
+/// ukulele's isn't the lowest in pitch.
+pub type Tuning = Vec<Pitch>;
+
+/// Standard EADGBE guitar tuning.
+pub const DEFAULT_TUNING: &[Pitch] = &[
+    Pitch::new(E, 2),
+    Pitch::new(A, 2),
+    Pitch::new(D, 3),
+    Pitch::new(G, 3),
+    Pitch::new(B, 3),
+    Pitch::new(E, 4),
+];
+
+/// Octave of the lowest string when a tuning doesn't say, where a guitar's low E is.
+const DEFAULT_OCTAVE: u8 = 2;
+
+/// Parse a tuning from its open-string note names, lowest string first.
+///
+/// Names can be separated by whitespace or commas (`"D A D G A D"`), or written together
+/// (`"DADGAD"`, `"EbAbDbGbBbEb"`), in which case every uppercase note letter starts a new note.
+/// Sharps and flats are both accepted.
+///
+/// Each name may give its octave, like `"G4 C4 E4 A4"`. A note without one is placed just above
+/// the previous string, or in the 2nd octave for the first string.
+pub fn parse_tuning(s: &str) -> Result<Tuning, ParseError> {
+    let mut names: Vec<String> = s
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|n| !n.is_empty())
+        .map(String::from)
+        .collect();
+    if names.len() == 1 {
+        // no separators, start a new note at every uppercase note letter
+        let joined = names.pop().unwrap();
+        for c in joined.chars() {
+            match names.last_mut() {
+                Some(name) if !matches!(c, 'A'..='G') => name.push(c),
+                _ => names.push(c.to_string()),
+            }
+        }
+    }
+    if names.is_empty() {
+        return Err(ParseError::InvalidTuning(s.to_string()));
+    }
+    let mut tuning: Tuning = Vec::with_capacity(names.len());
+    for name in names {
+        let pitch = match name.parse::<Pitch>() {
+            Ok(pitch) => pitch,
+            Err(_) => {
+                let note: Note = name.parse()?;
+                match tuning.last() {
+                    Some(&prev) => (1..=12)
+                        .map(|i| prev + i)
+                        .find(|p| p.note() == note)
+                        .unwrap(),
+                    None => Pitch::new(note, DEFAULT_OCTAVE),
+                }
+            }
+        };
+        tuning.push(pitch);
+    }
+    Ok(tuning)
+}
+
+/// Does the tuning string give the octave of its notes, rather than leaving it to
+/// [`parse_tuning`] to place them.
+pub fn has_octaves(s: &str) -> bool {
+    s.chars().any(|c| c.is_ascii_digit())
+}
+
+/// Shift the whole tuning by octaves so the lowest string is as close as possible to `reference`,
+/// e.g. to move a tuning given without octaves into the register of an instrument.
+pub fn place_near(t: &mut Tuning, reference: Pitch) {
+    let Some(first) = t.first() else { return };
+    let diff = reference.0 as i32 - first.0 as i32;
+    let octaves = (diff as f64 / 12.0).round() as i32;
+    for p in t.iter_mut() {
+        p.0 = (p.0 as i32 + octaves * 12).clamp(0, 127) as u8;
+    }
+}
+
+/// A named tuning that can be selected without spelling out its notes.
+#[derive(Debug, Clone, Copy)]
+pub struct TuningPreset {
+    /// Identifier used on the command line, e.g. `"open-g"`.
+    pub name: &'static str,
+    /// Human readable name, e.g. `"Open G"`.
+    pub description: &'static str,
+    /// Open-string pitches as accepted by [`parse_tuning`], e.g. `"D2 G2 D3 G3 B3 D4"`.
+    pub tuning: &'static str,
+}
+
+impl TuningPreset {
+    /// The open-string pitches of this preset.
+    pub fn pitches(&self) -> Tuning {
+        parse_tuning(self.tuning).unwrap()
+    }
+}
+
+/// All the built-in tuning presets, standard tuning first.
+pub const TUNING_PRESETS: &[TuningPreset] = &[
+    TuningPreset {
+        name: "standard",
+        description: "Standard",
+        tuning: "E2 A2 D3 G3 B3 E4",
+    },
+    TuningPreset {
+        name: "drop-d",
+        description: "Drop D",
+        tuning: "D2 A2 D3 G3 B3 E4",
+    },
+    TuningPreset {
+        name: "double-drop-d",
+        description: "Double Drop D",
+        tuning: "D2 A2 D3 G3 B3 D4",
+    },
+    TuningPreset {
+        name: "dadgad",
+        description: "DADGAD",
+        tuning: "D2 A2 D3 G3 A3 D4",
+    },
+    TuningPreset {
+        name: "open-g",
+        description: "Open G",
+        tuning: "D2 G2 D3 G3 B3 D4",
+    },
+    TuningPreset {
+        name: "open-d",
+        description: "Open D",
+        tuning: "D2 A2 D3 F#3 A3 D4",
+    },
+    TuningPreset {
+        name: "open-e",
+        description: "Open E",
+        tuning: "E2 B2 E3 G#3 B3 E4",
+    },
+    TuningPreset {
+        name: "open-a",
+        description: "Open A",
+        tuning: "E2 A2 E3 A3 C#4 E4",
+    },
+    TuningPreset {
+        name: "open-c",
+        description: "Open C",
+        tuning: "C2 G2 C3 G3 C4 E4",
+    },
+    TuningPreset {
+        name: "standard-eb",
+        description: "Standard Eb (half step down)",
+        tuning: "Eb2 Ab2 Db3 Gb3 Bb3 Eb4",
+    },
+    TuningPreset {
+        name: "standard-d",
+        description: "Standard D (whole step down)",
+        tuning: "D2 G2 C3 F3 A3 D4",
+    },
+    TuningPreset {
+        name: "drop-c",
+        description: "Drop C",
+        tuning: "C2 G2 C3 F3 A3 D4",
+    },
+    TuningPreset {
+        name: "seven-standard",
+        description: "7 string standard",
+        tuning: "B1 E2 A2 D3 G3 B3 E4",
+    },
+    TuningPreset {
+        name: "seven-drop-a",
+        description: "7 string drop A",
+        tuning: "A1 E2 A2 D3 G3 B3 E4",
+    },
+    TuningPreset {
+        name: "eight-standard",
+        description: "8 string standard",
+        tuning: "F#1 B1 E2 A2 D3 G3 B3 E4",
+    },
+    TuningPreset {
+        name: "eight-drop-e",
+        description: "8 string drop E",
+        tuning: "E1 B1 E2 A2 D3 G3 B3 E4",
+    },
+];
+
+/// Look up a preset by its name, ignoring case.
+pub fn tuning_preset(name: &str) -> Option<&'static TuningPreset> {
+    TUNING_PRESETS
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name))
+}
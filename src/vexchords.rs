@@ -0,0 +1,93 @@
+//! Describing voicings as VexChords charts.
+
+use crate::diagram::{barre_ends, diagram_frets, finger_label};
+use crate::Voicing;
+use serde::{ser::SerializeSeq, Serialize, Serializer};
+
+/// A chart of a voicing the way VexChords' `ChordBox.draw` takes it. Strings are numbered from
+/// 1 on the right, with the strings running down from the lowest on the left like
+/// [`ascii_diagram`](crate::ascii_diagram), and frets counted from the chart's `position`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VexChart {
+    /// Each string, from the one drawn on the left.
+    pub chord: Vec<VexString>,
+    /// The fret the chart starts at.
+    pub position: u8,
+    /// The barre, if there is one.
+    pub barres: Vec<VexBarre>,
+}
+
+/// A string of a [`VexChart`], serialized as `[string, fret]` with `"x"` for the fret of a muted
+/// string and `0` for an open one, followed by the finger holding it if that's known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VexString {
+    pub string: usize,
+    pub fret: Option<u8>,
+    pub finger: Option<char>,
+}
+
+/// A barre of a [`VexChart`] across the strings from `from_string` to `to_string`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VexBarre {
+    pub from_string: usize,
+    pub to_string: usize,
+    pub fret: u8,
+}
+
+impl VexChart {
+    /// The chart of `v`.
+    pub fn of(v: &Voicing) -> VexChart {
+        let position = *diagram_frets(v).start();
+        let strings = v.frets.len();
+        let relative = |fret: u8| fret - position + 1;
+        let chord = v
+            .frets
+            .iter()
+            .enumerate()
+            .map(|(i, f)| VexString {
+                string: strings - i,
+                fret: f.0.map(|fret| if fret == 0 { 0 } else { relative(fret) }),
+                finger: v
+                    .fingers
+                    .as_ref()
+                    .and_then(|h| h[i])
+                    .filter(|_| f.0.is_some_and(|fret| fret > 0))
+                    .map(finger_label),
+            })
+            .collect();
+        let barres = v
+            .barre
+            .as_ref()
+            .and_then(|b| {
+                let (first, last) = barre_ends(v, b.fret)?;
+                Some(VexBarre {
+                    from_string: strings - first,
+                    to_string: strings - last,
+                    fret: relative(b.fret),
+                })
+            })
+            .into_iter()
+            .collect();
+        VexChart {
+            chord,
+            position,
+            barres,
+        }
+    }
+}
+
+impl Serialize for VexString {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut seq = s.serialize_seq(Some(if self.finger.is_some() { 3 } else { 2 }))?;
+        seq.serialize_element(&self.string)?;
+        match self.fret {
+            Some(fret) => seq.serialize_element(&fret)?,
+            None => seq.serialize_element("x")?,
+        }
+        if let Some(finger) = self.finger {
+            seq.serialize_element(&finger)?;
+        }
+        seq.end()
+    }
+}
@@ -0,0 +1,49 @@
+//! How much each part of a fingering counts towards its score.
+
+use serde::Deserialize;
+
+/// Points used by [`fingering_score`](crate::fingering_score) and
+/// [`voicing_score`](crate::voicing_score) to rank voicings, configurable under `[weights]` in the
+/// config file.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Weights {
+    /// Points for each open string.
+    pub open: u32,
+    /// Points for each open string when playing in [`Options::position`](crate::Options::position).
+    pub open_in_position: u32,
+    /// Points for each string fretted at the first fret, shrinking the further up the neck it is
+    /// fretted, down to none past [`Options::max_fret`](crate::Options::max_fret).
+    pub fretted: u32,
+    /// Points for each muted string.
+    pub muted: u32,
+    /// Points for each fret the stretch is narrower than
+    /// [`Options::max_span`](crate::Options::max_span), plus two.
+    pub span: u32,
+    /// Points for each finger left free.
+    pub free_finger: u32,
+    /// Points lost for holding a barre.
+    pub barre: u32,
+    /// Points lost for each doubling of a tone other than the root and fifth, see
+    /// [`Doubling::penalty`](crate::Doubling::penalty).
+    pub doubling: u32,
+    /// Points lost for each pair of neighbouring strings sounding the same pitch, see
+    /// [`adjacent_unisons`](crate::adjacent_unisons).
+    pub unison: u32,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Weights {
+            open: 15,
+            open_in_position: 10,
+            fretted: 10,
+            muted: 10,
+            span: 1,
+            free_finger: 1,
+            barre: 0,
+            doubling: 3,
+            unison: 0,
+        }
+    }
+}